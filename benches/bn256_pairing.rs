@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate criterion;
+
+use criterion::{black_box, Criterion};
+use ff::Field;
+use group::Group;
+use pairing_bn256::arithmetic::MillerLoopResult;
+use pairing_bn256::bn256::{
+    multi_miller_loop, pairing, Fr, G1Affine, G2Affine, G2Prepared, Gt, G1, G2,
+};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a = G1Affine::from(G1::random(&mut rng));
+    let b = G2Affine::from(G2::random(&mut rng));
+    let b_prepared = G2Prepared::from(b);
+    let f = multi_miller_loop(&[(&a, &b_prepared)]);
+
+    c.bench_function("bn256 G2 preparation", |bencher| {
+        bencher.iter(|| G2Prepared::from(black_box(b)))
+    });
+    c.bench_function("bn256 miller loop", |bencher| {
+        bencher.iter(|| multi_miller_loop(&[(black_box(&a), black_box(&b_prepared))]))
+    });
+    c.bench_function("bn256 final exponentiation", |bencher| {
+        bencher.iter(|| black_box(f).final_exponentiation())
+    });
+    c.bench_function("bn256 full pairing", |bencher| {
+        bencher.iter(|| pairing(black_box(&a), black_box(&b)))
+    });
+
+    let e = pairing(&a, &b);
+    let e_compressed = e.compress();
+    c.bench_function("bn256 Gt compression", |bencher| {
+        bencher.iter(|| black_box(e).compress())
+    });
+    c.bench_function("bn256 Gt decompression", |bencher| {
+        bencher.iter(|| Gt::decompress(black_box(&e_compressed)))
+    });
+
+    let s = Fr::random(&mut rng);
+    c.bench_function("bn256 Gt exponentiation", |bencher| {
+        bencher.iter(|| black_box(e).pow(black_box(&s)))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
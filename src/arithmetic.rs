@@ -4,13 +4,24 @@
 //! This module is temporary, and the extension traits defined here are expected to be
 //! upstreamed into the `ff` and `group` crates after some refactoring.
 
+mod batch;
 mod curves;
+mod fft;
 mod fields;
 mod pairing;
+mod polynomial;
+mod safegcd;
 
+pub use batch::*;
 pub use curves::*;
+pub use fft::*;
 pub use fields::*;
 pub use pairing::*;
+pub use polynomial::*;
+pub(crate) use safegcd::safegcd_invert;
+
+use ff::PrimeField;
+use group::Group as _;
 
 /// This represents an element of a group with basic operations that can be
 /// performed. This allows an FFT implementation (for example) to operate
@@ -32,3 +43,151 @@ pub trait Group: Copy + Clone + Send + Sync + 'static {
     /// Scales this group element by a scalar.
     fn group_scale(&mut self, by: &Self::Scalar);
 }
+
+/// Splits `v` into one chunk per thread and calls `f` on each chunk together
+/// with the index of its first element. Without the `multicore` feature, `f`
+/// is called once on the whole slice.
+pub fn parallelize<T: Send, F: Fn(&mut [T], usize) + Send + Sync>(v: &mut [T], f: F) {
+    #[cfg(feature = "multicore")]
+    {
+        use rayon::prelude::*;
+
+        if v.is_empty() {
+            return;
+        }
+        let threads = rayon::current_num_threads();
+        let chunk_size = (v.len() + threads - 1) / threads;
+        v.par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, chunk)| f(chunk, i * chunk_size));
+    }
+
+    #[cfg(not(feature = "multicore"))]
+    f(v, 0);
+}
+
+/// Performs a multi-exponentiation operation with Pippenger's bucket method.
+///
+/// This function will panic if coeffs and bases have a different length.
+pub fn best_multiexp<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C]) -> C::Curve {
+    assert_eq!(coeffs.len(), bases.len());
+
+    let mut acc = C::Curve::identity();
+    multiexp_serial(coeffs, bases, &mut acc);
+    acc
+}
+
+fn multiexp_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C], acc: &mut C::Curve) {
+    let coeffs: Vec<_> = coeffs.iter().map(|a| a.to_repr()).collect();
+
+    let c = if bases.len() < 4 {
+        1
+    } else if bases.len() < 32 {
+        3
+    } else {
+        (f64::from(bases.len() as u32)).ln().ceil() as usize
+    };
+
+    fn get_at<F: PrimeField>(segment: usize, c: usize, bytes: &F::Repr) -> usize {
+        let skip_bits = segment * c;
+        let skip_bytes = skip_bits / 8;
+
+        if skip_bytes >= bytes.as_ref().len() {
+            return 0;
+        }
+
+        let mut v = [0; 8];
+        for (v, o) in v.iter_mut().zip(bytes.as_ref()[skip_bytes..].iter()) {
+            *v = *o;
+        }
+
+        let mut tmp = u64::from_le_bytes(v);
+        tmp >>= skip_bits - (skip_bytes * 8);
+        tmp %= 1 << c;
+
+        tmp as usize
+    }
+
+    let segments = (C::Scalar::NUM_BITS as usize / c) + 1;
+
+    for current_segment in (0..segments).rev() {
+        for _ in 0..c {
+            *acc = acc.double();
+        }
+
+        #[derive(Clone, Copy)]
+        enum Bucket<C: CurveAffine> {
+            None,
+            Affine(C),
+            Projective(C::Curve),
+        }
+
+        impl<C: CurveAffine> Bucket<C> {
+            fn add_assign(&mut self, other: &C) {
+                *self = match *self {
+                    Bucket::None => Bucket::Affine(*other),
+                    Bucket::Affine(a) => Bucket::Projective(a + *other),
+                    Bucket::Projective(mut a) => {
+                        a += *other;
+                        Bucket::Projective(a)
+                    }
+                }
+            }
+
+            fn add(self, mut other: C::Curve) -> C::Curve {
+                match self {
+                    Bucket::None => other,
+                    Bucket::Affine(a) => {
+                        other += a;
+                        other
+                    }
+                    Bucket::Projective(a) => other + a,
+                }
+            }
+        }
+
+        let mut buckets: Vec<Bucket<C>> = vec![Bucket::None; (1 << c) - 1];
+
+        for (coeff, base) in coeffs.iter().zip(bases.iter()) {
+            let coeff = get_at::<C::Scalar>(current_segment, c, coeff);
+            if coeff != 0 {
+                buckets[coeff - 1].add_assign(base);
+            }
+        }
+
+        // Summation by parts
+        // e.g. 3a + 2b + 1c = a +
+        //                    (a) + b +
+        //                    ((a) + b) + c
+        let mut running_sum = C::Curve::identity();
+        for exp in buckets.into_iter().rev() {
+            running_sum = exp.add(running_sum);
+            *acc += running_sum;
+        }
+    }
+}
+
+#[test]
+fn test_best_multiexp() {
+    use crate::bn256::{Fr, G1Affine, G1};
+    use ff::Field;
+    use group::Curve;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for n in [1, 3, 10, 100] {
+        let coeffs: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+        let bases: Vec<G1Affine> = (0..n).map(|_| G1::random(&mut rng).to_affine()).collect();
+
+        let expected = coeffs
+            .iter()
+            .zip(bases.iter())
+            .fold(G1::identity(), |acc, (coeff, base)| acc + base * coeff);
+        assert_eq!(best_multiexp(&coeffs, &bases), expected);
+    }
+}
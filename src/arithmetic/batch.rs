@@ -0,0 +1,195 @@
+//! This module contains a batch verifier for pairing product equations.
+
+use super::{best_multiexp, MultiMillerLoop};
+use ff::Field;
+use group::{Curve, GroupEncoding};
+use rand_core::RngCore;
+use std::collections::HashMap;
+use subtle::Choice;
+
+/// Accumulates many pairing product equations $$\prod_j e(a_j, b_j) = 1$$
+/// and checks all of them at once.
+///
+/// Each equation is scaled by a random challenge $r_i$, and the G1 terms
+/// paired with the same G2 base are combined into a single multi-scalar
+/// multiplication, so that the whole batch costs one Miller loop per
+/// distinct G2 base and a single final exponentiation. A batch containing
+/// an invalid equation is accepted with probability at most $1/|F_r|$.
+///
+/// Each G2 base is passed together with its prepared form, which must be
+/// `E::G2Prepared::from(b)`, so that callers holding prepared points do not
+/// pay for preparing them again. Terms are merged on the encoding of the G2
+/// base itself, and the prepared form is kept from the first term with that
+/// base.
+#[derive(Clone, Debug)]
+pub struct PairingBatchVerifier<E: MultiMillerLoop> {
+    // distinct prepared G2 bases
+    bases: Vec<E::G2Prepared>,
+    // G1 points paired with each base, and the scalars they are scaled by
+    points: Vec<Vec<E::G1Affine>>,
+    scalars: Vec<Vec<E::Scalar>>,
+    // index into `bases`, keyed by the encoding of the G2 base
+    positions: HashMap<Vec<u8>, usize>,
+}
+
+impl<E: MultiMillerLoop> Default for PairingBatchVerifier<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: MultiMillerLoop> PairingBatchVerifier<E> {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        PairingBatchVerifier {
+            bases: vec![],
+            points: vec![],
+            scalars: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of distinct G2 bases, i.e. the number of Miller
+    /// loops `verify` will run.
+    pub fn num_bases(&self) -> usize {
+        self.bases.len()
+    }
+
+    /// Adds the equation $$\prod_j e(a_j, b_j) = 1$$ to the batch, scaled by
+    /// a challenge sampled from `rng`.
+    pub fn add_equation(
+        &mut self,
+        terms: &[(&E::G1Affine, &E::G2Affine, &E::G2Prepared)],
+        mut rng: impl RngCore,
+    ) {
+        let challenge = loop {
+            let challenge = E::Scalar::random(&mut rng);
+            if !bool::from(challenge.is_zero()) {
+                break challenge;
+            }
+        };
+        self.add_equation_with_challenge(terms, challenge);
+    }
+
+    /// Adds the equation $$\prod_j e(a_j, b_j) = 1$$ to the batch, scaled by
+    /// the given challenge. The challenge must be unpredictable to whoever
+    /// produced the terms.
+    pub fn add_equation_with_challenge(
+        &mut self,
+        terms: &[(&E::G1Affine, &E::G2Affine, &E::G2Prepared)],
+        challenge: E::Scalar,
+    ) {
+        for &(a, b, b_prepared) in terms {
+            let position = match self.positions.get(b.to_bytes().as_ref()) {
+                Some(&position) => position,
+                None => {
+                    self.positions
+                        .insert(b.to_bytes().as_ref().to_vec(), self.bases.len());
+                    self.bases.push(b_prepared.clone());
+                    self.points.push(vec![]);
+                    self.scalars.push(vec![]);
+                    self.bases.len() - 1
+                }
+            };
+            self.points[position].push(*a);
+            self.scalars[position].push(challenge);
+        }
+    }
+
+    /// Checks all accumulated equations with a single final exponentiation.
+    pub fn verify(&self) -> Choice {
+        let points: Vec<E::G1Affine> = self
+            .points
+            .iter()
+            .zip(self.scalars.iter())
+            .map(|(points, scalars)| best_multiexp(scalars, points).to_affine())
+            .collect();
+        let terms: Vec<(&E::G1Affine, &E::G2Prepared)> =
+            points.iter().zip(self.bases.iter()).collect();
+
+        E::pairing_check(&terms)
+    }
+}
+
+#[cfg(test)]
+use crate::bn256::{Bn256, Fr, G1Affine, G2Affine, G2Prepared, G1, G2};
+#[cfg(test)]
+use group::{prime::PrimeCurveAffine, Group};
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_pairing_batch_verifier() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // e(sP_i, Q) * e(-P_i, H) = 1 with H = sQ shared by all equations
+    let q = G2::random(&mut rng);
+    let s = Fr::random(&mut rng);
+    let h = (q * s).to_affine();
+    let q = q.to_affine();
+    let (q_prepared, h_prepared) = (G2Prepared::from(q), G2Prepared::from(h));
+
+    let mut batch = PairingBatchVerifier::<Bn256>::new();
+    assert!(bool::from(batch.verify()));
+
+    let mut equations = vec![];
+    for _ in 0..20 {
+        let p = G1::random(&mut rng);
+        equations.push(((p * s).to_affine(), (-p).to_affine()));
+    }
+    for (sp, neg_p) in equations.iter() {
+        batch.add_equation(&[(sp, &q, &q_prepared), (neg_p, &h, &h_prepared)], &mut rng);
+    }
+    assert_eq!(batch.num_bases(), 2);
+    assert!(bool::from(batch.verify()));
+
+    // Equations with their own G2 bases
+    for _ in 0..5 {
+        let p = G1::random(&mut rng).to_affine();
+        let t = G2::random(&mut rng);
+        let r = Fr::random(&mut rng);
+        let rt = (t * r).to_affine();
+        let neg_rp = (-(p * r)).to_affine();
+        let t = t.to_affine();
+        batch.add_equation(
+            &[
+                (&p, &rt, &G2Prepared::from(rt)),
+                (&neg_rp, &t, &G2Prepared::from(t)),
+            ],
+            &mut rng,
+        );
+    }
+    assert_eq!(batch.num_bases(), 12);
+    assert!(bool::from(batch.verify()));
+
+    // A single invalid equation makes the whole batch fail
+    let mut invalid = batch.clone();
+    let p = G1::random(&mut rng).to_affine();
+    invalid.add_equation(
+        &[
+            (&p, &q, &q_prepared),
+            (&G1Affine::identity(), &h, &h_prepared),
+        ],
+        &mut rng,
+    );
+    assert!(!bool::from(invalid.verify()));
+
+    // A base that differs from the ones in the batch is not merged with them
+    let mut invalid = batch.clone();
+    let (sp, neg_p) = equations[1];
+    let zero = G2Affine::identity();
+    invalid.add_equation(
+        &[
+            (&sp, &q, &q_prepared),
+            (&neg_p, &zero, &G2Prepared::from(zero)),
+        ],
+        &mut rng,
+    );
+    assert_eq!(invalid.num_bases(), 13);
+    assert!(!bool::from(invalid.verify()));
+}
@@ -3,6 +3,7 @@
 
 use super::{BaseExt, FieldExt, Group};
 use core::ops::{Add, Mul, Sub};
+use ff::Field;
 use group::prime::{PrimeCurve, PrimeCurveAffine};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -52,6 +53,28 @@ pub trait CurveExt:
     fn new_jacobian(x: Self::Base, y: Self::Base, z: Self::Base) -> CtOption<Self>;
 }
 
+/// A function hashing messages to points of `C`.
+pub type CurveHasher<'a, C> = Box<dyn Fn(&[u8]) -> C + 'a>;
+
+/// This trait is implemented by the curves that messages can be hashed to.
+pub trait HashToCurve: CurveExt {
+    /// The identifier of the hash to curve suite, as in Section 8.10 of
+    /// RFC 9380, such as `BN254G1_XMD:SHA-256_SVDW_RO_`. Suites that are not
+    /// known to agree with other implementations have a `MAP_ID` naming
+    /// this crate.
+    const SUITE_ID: &'static str;
+
+    /// Returns a function that hashes messages to points of the prime order
+    /// subgroup, with a domain separation tag derived from `domain_prefix`
+    /// and `CURVE_ID`.
+    fn hash_to_curve(domain_prefix: &str) -> CurveHasher<'_, Self>;
+
+    /// Returns a function that hashes messages to points of the prime order
+    /// subgroup with the suite `SUITE_ID` and the domain separation tag
+    /// `dst`, which must be at most 255 bytes long.
+    fn hash_to_curve_with_dst(dst: &[u8]) -> CurveHasher<'_, Self>;
+}
+
 /// This trait is the affine counterpart to `Curve` and is used for
 /// serialization, storage in memory, and inspection of $x$ and $y$ coordinates.
 
@@ -157,3 +180,10 @@ impl<C: CurveAffine> ConditionallySelectable for Coordinates<C> {
         }
     }
 }
+
+/// Returns whether $[r] P$ is the identity, where $r$ is the order of the
+/// scalar field.
+pub(crate) fn is_torsion_free<C: CurveAffine>(point: &C) -> bool {
+    // [r] P = 0 if and only if [r - 1] P = -P
+    *point * -C::ScalarExt::one() == -point.to_curve()
+}
@@ -0,0 +1,404 @@
+//! This module contains a radix-2 FFT over the multiplicative subgroups of
+//! order $2^k$ of a prime field, working over any `Group` whose scalar field
+//! has enough 2-adicity.
+
+use super::{parallelize, FieldExt, Group};
+use ff::{Field, PrimeField};
+
+/// Performs a radix-2 FFT in place on `a`, whose length must be $2^{log_n}$,
+/// where `omega` is a primitive $2^{log_n}$-th root of unity. With the
+/// `multicore` feature, the butterflies are split across threads.
+pub fn best_fft<G: Group>(a: &mut [G], omega: G::Scalar, log_n: u32) {
+    assert_eq!(a.len(), 1 << log_n);
+
+    #[cfg(feature = "multicore")]
+    {
+        let threads = rayon::current_num_threads();
+        let log_threads = usize::BITS - 1 - threads.leading_zeros();
+        if log_threads > 0 && log_n > log_threads {
+            parallel_fft(a, &omega, log_n, log_threads);
+            return;
+        }
+    }
+
+    serial_fft(a, &omega, log_n);
+}
+
+fn bitreverse(mut n: usize, l: u32) -> usize {
+    let mut r = 0;
+    for _ in 0..l {
+        r = (r << 1) | (n & 1);
+        n >>= 1;
+    }
+    r
+}
+
+fn serial_fft<G: Group>(a: &mut [G], omega: &G::Scalar, log_n: u32) {
+    let n = a.len();
+
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk, k);
+        }
+    }
+
+    let mut m = 1;
+    for _ in 0..log_n {
+        let w_m = omega.pow_vartime([(n / (2 * m)) as u64, 0, 0, 0]);
+
+        let mut k = 0;
+        while k < n {
+            let mut w = G::Scalar::one();
+            for j in 0..m {
+                let mut t = a[k + j + m];
+                t.group_scale(&w);
+                let mut tmp = a[k + j];
+                tmp.group_sub(&t);
+                a[k + j + m] = tmp;
+                a[k + j].group_add(&t);
+                w *= &w_m;
+            }
+
+            k += 2 * m;
+        }
+
+        m *= 2;
+    }
+}
+
+/// Splits the FFT into $2^{log_threads}$ independent FFTs of size
+/// $2^{log_n - log_threads}$, one per thread.
+#[cfg(feature = "multicore")]
+fn parallel_fft<G: Group>(a: &mut [G], omega: &G::Scalar, log_n: u32, log_threads: u32) {
+    use rayon::prelude::*;
+
+    let num_threads = 1 << log_threads;
+    let log_new_n = log_n - log_threads;
+    let new_omega = omega.pow_vartime([num_threads as u64, 0, 0, 0]);
+
+    let mut tmp = vec![vec![G::group_zero(); 1 << log_new_n]; num_threads];
+    let input = &*a;
+    tmp.par_iter_mut().enumerate().for_each(|(j, tmp)| {
+        // Shuffle into a sub-FFT
+        let omega_j = omega.pow_vartime([j as u64, 0, 0, 0]);
+        let omega_step = omega.pow_vartime([(j as u64) << log_new_n, 0, 0, 0]);
+
+        let mut elt = G::Scalar::one();
+        for (i, tmp) in tmp.iter_mut().enumerate() {
+            for s in 0..num_threads {
+                let idx = (i + (s << log_new_n)) % (1 << log_n);
+                let mut t = input[idx];
+                t.group_scale(&elt);
+                tmp.group_add(&t);
+                elt *= &omega_step;
+            }
+            elt *= &omega_j;
+        }
+
+        serial_fft(tmp, &new_omega, log_new_n);
+    });
+
+    // Unshuffle
+    let mask = (1 << log_threads) - 1;
+    a.par_iter_mut()
+        .enumerate()
+        .for_each(|(idx, a)| *a = tmp[idx & mask][idx >> log_threads]);
+}
+
+/// The evaluation domain of size $n = 2^k$ of a polynomial, or of a vector
+/// of group elements, stored as coefficients or evaluations over the powers
+/// of a primitive $n$-th root of unity $\omega$.
+#[derive(Clone, Debug)]
+pub struct EvaluationDomain<G: Group> {
+    coeffs: Vec<G>,
+    exp: u32,
+    omega: G::Scalar,
+    omega_inv: G::Scalar,
+    delta_inv: G::Scalar,
+    n_inv: G::Scalar,
+}
+
+impl<G: Group> AsRef<[G]> for EvaluationDomain<G> {
+    fn as_ref(&self) -> &[G] {
+        &self.coeffs
+    }
+}
+
+impl<G: Group> AsMut<[G]> for EvaluationDomain<G> {
+    fn as_mut(&mut self) -> &mut [G] {
+        &mut self.coeffs
+    }
+}
+
+impl<G: Group> EvaluationDomain<G> {
+    /// Creates a domain from `coeffs`, padded with zeros up to the next power
+    /// of two. Returns `None` if the scalar field has no subgroup of that
+    /// size.
+    pub fn from_coeffs(mut coeffs: Vec<G>) -> Option<Self> {
+        let mut n = 1;
+        let mut exp = 0;
+        while n < coeffs.len() {
+            n *= 2;
+            exp += 1;
+            if exp > G::Scalar::S {
+                return None;
+            }
+        }
+
+        // omega = ROOT_OF_UNITY^(2^(S - exp)) has order 2^exp
+        let mut omega = G::Scalar::root_of_unity();
+        for _ in exp..G::Scalar::S {
+            omega = omega.square();
+        }
+
+        coeffs.resize(n, G::group_zero());
+
+        Some(EvaluationDomain {
+            coeffs,
+            exp,
+            omega,
+            omega_inv: omega.invert().unwrap(),
+            delta_inv: G::Scalar::DELTA.invert().unwrap(),
+            n_inv: G::Scalar::from(n as u64).invert().unwrap(),
+        })
+    }
+
+    /// Returns the coefficients, or evaluations, of this domain.
+    pub fn into_coeffs(self) -> Vec<G> {
+        self.coeffs
+    }
+
+    /// Returns $k$, where the size of this domain is $2^k$.
+    pub fn exp(&self) -> u32 {
+        self.exp
+    }
+
+    /// Returns the primitive root of unity $\omega$ generating this domain.
+    pub fn omega(&self) -> G::Scalar {
+        self.omega
+    }
+
+    /// Converts coefficients into evaluations over the domain.
+    pub fn fft(&mut self) {
+        best_fft(&mut self.coeffs, self.omega, self.exp);
+    }
+
+    /// Converts evaluations over the domain back into coefficients.
+    pub fn ifft(&mut self) {
+        best_fft(&mut self.coeffs, self.omega_inv, self.exp);
+
+        let n_inv = self.n_inv;
+        parallelize(&mut self.coeffs, |chunk, _| {
+            for v in chunk {
+                v.group_scale(&n_inv);
+            }
+        });
+    }
+
+    /// Converts coefficients into evaluations over the coset $\delta H$ of the
+    /// domain $H$, where $\delta$ is `FieldExt::DELTA`.
+    pub fn coset_fft(&mut self) {
+        self.distribute_powers(G::Scalar::DELTA);
+        self.fft();
+    }
+
+    /// Converts evaluations over the coset $\delta H$ back into coefficients.
+    pub fn icoset_fft(&mut self) {
+        let delta_inv = self.delta_inv;
+        self.ifft();
+        self.distribute_powers(delta_inv);
+    }
+
+    /// Scales the $i$-th coefficient by $g^i$, which turns the polynomial
+    /// $p(X)$ into $p(gX)$.
+    pub fn distribute_powers(&mut self, g: G::Scalar) {
+        parallelize(&mut self.coeffs, |chunk, start| {
+            let mut u = g.pow_vartime([start as u64, 0, 0, 0]);
+            for v in chunk {
+                v.group_scale(&u);
+                u *= &g;
+            }
+        });
+    }
+
+    /// Evaluates the vanishing polynomial $Z(X) = X^n - 1$ of the domain at
+    /// `tau`.
+    pub fn z(&self, tau: &G::Scalar) -> G::Scalar {
+        let mut tmp = tau.pow_vartime([self.coeffs.len() as u64, 0, 0, 0]);
+        tmp -= &G::Scalar::one();
+        tmp
+    }
+
+    /// Divides evaluations over the coset $\delta H$ by the vanishing
+    /// polynomial, which is the constant $\delta^n - 1$ on that coset.
+    pub fn divide_by_z_on_coset(&mut self) {
+        let z_inv = self.z(&G::Scalar::DELTA).invert().unwrap();
+        parallelize(&mut self.coeffs, |chunk, _| {
+            for v in chunk {
+                v.group_scale(&z_inv);
+            }
+        });
+    }
+
+    /// Multiplies the evaluations of this domain pointwise by those of
+    /// `other`.
+    pub fn mul_assign(&mut self, other: &EvaluationDomain<G::Scalar>) {
+        assert_eq!(self.coeffs.len(), other.coeffs.len());
+
+        parallelize(&mut self.coeffs, |chunk, start| {
+            for (v, b) in chunk.iter_mut().zip(other.coeffs[start..].iter()) {
+                v.group_scale(b);
+            }
+        });
+    }
+
+    /// Subtracts the evaluations of `other` from those of this domain.
+    pub fn sub_assign(&mut self, other: &EvaluationDomain<G>) {
+        assert_eq!(self.coeffs.len(), other.coeffs.len());
+
+        parallelize(&mut self.coeffs, |chunk, start| {
+            for (v, b) in chunk.iter_mut().zip(other.coeffs[start..].iter()) {
+                v.group_sub(b);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+use crate::bn256::{Fr, G1};
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[cfg(test)]
+fn eval(coeffs: &[Fr], x: Fr) -> Fr {
+    coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+}
+
+#[test]
+fn test_fft() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for size in [1, 2, 5, 16, 100, 1000] {
+        let coeffs: Vec<Fr> = (0..size).map(|_| Fr::random(&mut rng)).collect();
+        let mut domain = EvaluationDomain::from_coeffs(coeffs.clone()).unwrap();
+        let n = 1 << domain.exp();
+        assert!(n >= size && n < 2 * size.max(1));
+        assert_eq!(domain.omega().pow_vartime([n as u64, 0, 0, 0]), Fr::one());
+
+        domain.fft();
+        let mut x = Fr::one();
+        for y in domain.as_ref() {
+            assert_eq!(*y, eval(&coeffs, x));
+            x *= domain.omega();
+        }
+
+        domain.ifft();
+        assert_eq!(&domain.as_ref()[..size], &coeffs[..]);
+
+        let mut coset = EvaluationDomain::from_coeffs(coeffs.clone()).unwrap();
+        coset.coset_fft();
+        assert_eq!(coset.as_ref()[0], eval(&coeffs, Fr::DELTA));
+        coset.icoset_fft();
+        assert_eq!(&coset.as_ref()[..size], &coeffs[..]);
+    }
+}
+
+#[test]
+fn test_divide_by_z_on_coset() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // c interpolates a * b over the domain, so a * b - c = h * Z
+    let n = 64;
+    let a: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+    let b: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+    let mut c = EvaluationDomain::from_coeffs(a.clone()).unwrap();
+    let mut b_evals = EvaluationDomain::from_coeffs(b.clone()).unwrap();
+    c.fft();
+    b_evals.fft();
+    c.mul_assign(&b_evals);
+    c.ifft();
+    let c = c.into_coeffs();
+
+    let mut h = EvaluationDomain::from_coeffs(a.clone()).unwrap();
+    let mut b_coset = EvaluationDomain::from_coeffs(b.clone()).unwrap();
+    let mut c_coset = EvaluationDomain::from_coeffs(c.clone()).unwrap();
+    h.coset_fft();
+    b_coset.coset_fft();
+    c_coset.coset_fft();
+    h.mul_assign(&b_coset);
+    h.sub_assign(&c_coset);
+    h.divide_by_z_on_coset();
+    h.icoset_fft();
+
+    let tau = Fr::random(&mut rng);
+    assert_eq!(h.z(&tau), tau.pow_vartime([n as u64, 0, 0, 0]) - Fr::one());
+    assert_eq!(
+        eval(&a, tau) * eval(&b, tau) - eval(&c, tau),
+        eval(h.as_ref(), tau) * h.z(&tau)
+    );
+}
+
+#[test]
+fn test_fft_g1() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // The FFT commutes with scaling the generator
+    let g = G1::generator();
+    let scalars: Vec<Fr> = (0..32).map(|_| Fr::random(&mut rng)).collect();
+    let points: Vec<G1> = scalars.iter().map(|s| g * s).collect();
+
+    let mut scalars = EvaluationDomain::from_coeffs(scalars).unwrap();
+    let mut points = EvaluationDomain::from_coeffs(points).unwrap();
+    scalars.fft();
+    points.fft();
+    for (s, p) in scalars.as_ref().iter().zip(points.as_ref().iter()) {
+        assert_eq!(g * s, *p);
+    }
+
+    // Lagrange basis of an SRS: the inverse FFT of [tau^i]G gives [L_i(tau)]G
+    let tau = Fr::random(&mut rng);
+    let powers: Vec<G1> = (0..32)
+        .map(|i| g * tau.pow_vartime([i as u64, 0, 0, 0]))
+        .collect();
+    let mut lagrange = EvaluationDomain::from_coeffs(powers).unwrap();
+    lagrange.ifft();
+    let mut basis = vec![Fr::zero(); 32];
+    basis[3] = Fr::one();
+    let mut l3 = EvaluationDomain::from_coeffs(basis).unwrap();
+    l3.ifft();
+    assert_eq!(lagrange.as_ref()[3], g * eval(l3.as_ref(), tau));
+}
+
+#[cfg(feature = "multicore")]
+#[test]
+fn test_parallel_fft() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let log_n = 10;
+    let omega = EvaluationDomain::<Fr>::from_coeffs(vec![Fr::zero(); 1 << log_n])
+        .unwrap()
+        .omega();
+    for log_threads in 1..4 {
+        let a: Vec<Fr> = (0..1 << log_n).map(|_| Fr::random(&mut rng)).collect();
+        let mut serial = a.clone();
+        let mut parallel = a;
+        serial_fft(&mut serial, &omega, log_n);
+        parallel_fft(&mut parallel, &omega, log_n, log_threads);
+        assert_eq!(serial, parallel);
+    }
+}
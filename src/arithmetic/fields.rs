@@ -51,6 +51,44 @@ pub trait BaseExt: ff::Field + Ord + ConstantTimeEq {
         }
         res
     }
+
+    /// Exponentiates `self` by `by` in variable time, using a sliding window
+    /// of width 4. This must only be used when both the base and the exponent
+    /// are public.
+    fn pow_vartime_sliding(&self, by: &[u64; 4]) -> Self {
+        const WINDOW: usize = 4;
+        let bit = |i: usize| (by[i / 64] >> (i % 64)) & 1;
+
+        // Odd powers self, self^3, ..., self^(2^WINDOW - 1)
+        let mut table = [*self; 1 << (WINDOW - 1)];
+        let square = self.square();
+        for i in 1..table.len() {
+            table[i] = table[i - 1] * square;
+        }
+
+        let mut res = Self::one();
+        let mut i = 256;
+        while i > 0 {
+            if bit(i - 1) == 0 {
+                res = res.square();
+                i -= 1;
+            } else {
+                // Take the longest window of at most WINDOW bits ending in a one
+                let mut len = WINDOW.min(i);
+                while bit(i - len) == 0 {
+                    len -= 1;
+                }
+                let mut window = 0;
+                for j in (i - len..i).rev() {
+                    res = res.square();
+                    window = (window << 1) | bit(j);
+                }
+                res *= table[(window >> 1) as usize];
+                i -= len;
+            }
+        }
+        res
+    }
 }
 
 pub trait FieldExt: ff::PrimeField + BaseExt + Group<Scalar = Self> + From<bool> {
@@ -82,6 +120,40 @@ pub trait FieldExt: ff::PrimeField + BaseExt + Group<Scalar = Self> + From<bool>
     fn get_lower_128(&self) -> u128;
 }
 
+/// The Legendre symbol $(a / p)$ of a field element $a$.
+#[derive(Debug, PartialEq)]
+pub enum LegendreSymbol {
+    Zero = 0,
+    QuadraticResidue = 1,
+    QuadraticNonResidue = -1,
+}
+
+/// This trait allows querying the quadratic residuosity of field elements.
+pub trait Legendre: ff::Field {
+    /// Computes the Legendre symbol of this element as $0$, $1$ or $-1$, in
+    /// constant time.
+    fn jacobi(&self) -> i64;
+
+    /// Returns the Legendre symbol of this element.
+    fn legendre(&self) -> LegendreSymbol {
+        match self.jacobi() {
+            0 => LegendreSymbol::Zero,
+            1 => LegendreSymbol::QuadraticResidue,
+            _ => LegendreSymbol::QuadraticNonResidue,
+        }
+    }
+
+    /// Returns whether this element is a square, including zero.
+    fn ct_quadratic_residue(&self) -> Choice {
+        !self.ct_quadratic_non_residue()
+    }
+
+    /// Returns whether this element is a quadratic non-residue.
+    fn ct_quadratic_non_residue(&self) -> Choice {
+        self.jacobi().ct_eq(&-1)
+    }
+}
+
 /// Compute a + b + carry, returning the result and the new carry over.
 #[inline(always)]
 pub(crate) const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
@@ -6,6 +6,7 @@ use group::{
 };
 
 use super::FieldExt;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 pub trait Engine: Sized + 'static + Clone {
     /// This is the scalar field of the engine's groups.
@@ -53,8 +54,8 @@ pub trait Engine: Sized + 'static + Clone {
     type Gt: Group<Scalar = Self::Scalar>
         + ScalarMul<Self::Scalar>
         + ScalarMulOwned<Self::Scalar>
-        + ff::Field
-        + MillerLoopResult<Gt = Self::Gt>;
+        + ConditionallySelectable
+        + ConstantTimeEq;
 
     /// Invoke the pairing function `G1 x G2 -> Gt` without the use of precomputation and
     /// other optimizations.
@@ -75,10 +76,49 @@ pub trait PairingCurveAffine: CurveAffine + UncompressedEncoding {
 pub trait MultiMillerLoop: Engine {
     /// The prepared form of `Self::G2Affine`.
     type G2Prepared: Clone + Send + Sync + From<Self::G2Affine>;
+    /// The type returned by `Engine::multi_miller_loop`.
+    type Result: MillerLoopResult<Gt = Self::Gt>;
 
     /// Computes $$\sum_{i=1}^n \textbf{ML}(a_i, b_i)$$ given a series of terms
     /// $$(a_1, b_1), (a_2, b_2), ..., (a_n, b_n).$$
-    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Gt;
+    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result;
+
+    /// Checks whether $$\prod_{i=1}^n e(a_i, b_i) = 1$$ for a series of terms
+    /// $$(a_1, b_1), (a_2, b_2), ..., (a_n, b_n).$$ Terms with an identity on
+    /// either side contribute $1$ to the product.
+    fn pairing_check(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Choice {
+        Self::multi_miller_loop(terms).is_one_after_final_exponentiation()
+    }
+}
+
+/// A way of evaluating the Miller loop of an engine, given by the form in
+/// which the G2 points are prepared and the line functions are evaluated.
+/// Different strategies may give different Miller loop values for the same
+/// terms, but these agree after the final exponentiation.
+pub trait MillerLoopStrategy<E: MultiMillerLoop> {
+    /// The prepared form of `E::G2Affine` used by this strategy.
+    type G2Prepared: Clone + Send + Sync + From<E::G2Affine>;
+
+    /// Computes $$\sum_{i=1}^n \textbf{ML}(a_i, b_i)$$ given a series of terms
+    /// $$(a_1, b_1), (a_2, b_2), ..., (a_n, b_n).$$
+    fn multi_miller_loop(terms: &[(&E::G1Affine, &Self::G2Prepared)]) -> E::Result;
+
+    /// Checks with the witnesses `c` and `wi` of "On Proving Pairings" that
+    /// $$\prod_{i=1}^n e(a_i, b_i) = 1$$, by checking that the Miller loop of
+    /// the terms combined with `c` and `wi` is the identity. The witnesses
+    /// must be computed from this strategy's Miller loop value, and may come
+    /// from an untrusted prover.
+    fn pairing_check_c_wi(
+        c: &E::Gt,
+        wi: &E::Gt,
+        terms: &[(&E::G1Affine, &Self::G2Prepared)],
+    ) -> Choice;
+
+    /// Checks whether $$\prod_{i=1}^n e(a_i, b_i) = 1$$ for a series of terms
+    /// $$(a_1, b_1), (a_2, b_2), ..., (a_n, b_n).$$
+    fn pairing_check(terms: &[(&E::G1Affine, &Self::G2Prepared)]) -> Choice {
+        Self::multi_miller_loop(terms).is_one_after_final_exponentiation()
+    }
 }
 
 //refer "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
@@ -122,6 +162,17 @@ pub trait MultiMillerLoopOnProvePairing: MultiMillerLoop {
     fn get_g2_on_prove_prepared_init_q(p: &Self::G2OnProvePrepared) -> Self::G2Affine;
 }
 
+/// The Miller loop strategies of an engine supporting "On Proving Pairings",
+/// which can be picked between by callers.
+pub trait MillerLoopStrategies: MultiMillerLoopOnProvePairing {
+    /// The Miller loop over `Self::G2Prepared`, with line functions evaluated
+    /// in projective coordinates.
+    type ProjectiveStrategy: MillerLoopStrategy<Self, G2Prepared = Self::G2Prepared>;
+    /// The Miller loop over `Self::G2OnProvePrepared`, with line functions
+    /// evaluated in affine coordinates.
+    type AffineStrategy: MillerLoopStrategy<Self, G2Prepared = Self::G2OnProvePrepared>;
+}
+
 /// Represents results of a Miller loop, one of the most expensive portions of the pairing
 /// function.
 ///
@@ -135,4 +186,11 @@ pub trait MillerLoopResult {
     /// loop into an element of [`MillerLoopResult::Gt`], so that it can be compared with
     /// other elements of `Gt`.
     fn final_exponentiation(&self) -> Self::Gt;
+
+    /// Returns whether the final exponentiation of this Miller loop result is
+    /// the identity. Implementations may override this with a check that is
+    /// cheaper than a full final exponentiation.
+    fn is_one_after_final_exponentiation(&self) -> Choice {
+        self.final_exponentiation().is_identity()
+    }
 }
@@ -0,0 +1,378 @@
+//! This module contains univariate polynomials over a prime field, stored
+//! either by their coefficients or by their evaluations over the powers of a
+//! root of unity.
+
+use super::{parallelize, EvaluationDomain, FieldExt};
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Sub};
+
+/// The representation of a `Polynomial`.
+pub trait Basis: Copy + Debug + Send + Sync + PartialEq + Eq {}
+
+/// The coefficient representation, $p(X) = \sum_i p_i X^i$.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Coeff;
+impl Basis for Coeff {}
+
+/// The evaluation representation, $p(\omega^i)$ for $i < n$ where $\omega$ is
+/// a primitive $n$-th root of unity and $n$ is a power of two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LagrangeCoeff;
+impl Basis for LagrangeCoeff {}
+
+/// A univariate polynomial over `F`, in the representation `B`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<F: FieldExt, B: Basis = Coeff> {
+    values: Vec<F>,
+    _marker: PhantomData<B>,
+}
+
+impl<F: FieldExt, B: Basis> AsRef<[F]> for Polynomial<F, B> {
+    fn as_ref(&self) -> &[F] {
+        &self.values
+    }
+}
+
+impl<F: FieldExt, B: Basis> Polynomial<F, B> {
+    fn new(values: Vec<F>) -> Self {
+        Polynomial {
+            values,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of coefficients or evaluations.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether this polynomial has no coefficients or evaluations.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the coefficients or evaluations.
+    pub fn into_values(self) -> Vec<F> {
+        self.values
+    }
+
+    /// Scales this polynomial by `by`.
+    pub fn scale(&self, by: F) -> Self {
+        let mut values = self.values.clone();
+        parallelize(&mut values, |chunk, _| {
+            for v in chunk {
+                *v *= by;
+            }
+        });
+        Self::new(values)
+    }
+}
+
+impl<F: FieldExt> Polynomial<F, Coeff> {
+    /// Creates a polynomial from its coefficients, in increasing degree.
+    pub fn from_coeffs(coeffs: Vec<F>) -> Self {
+        Self::new(coeffs)
+    }
+
+    /// Returns the zero polynomial.
+    pub fn zero() -> Self {
+        Self::new(vec![])
+    }
+
+    /// Returns the degree of this polynomial, taking zero to be of degree 0.
+    pub fn degree(&self) -> usize {
+        self.values
+            .iter()
+            .rposition(|c| !bool::from(c.ct_is_zero()))
+            .unwrap_or(0)
+    }
+
+    /// Evaluates this polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: F) -> F {
+        self.values
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, coeff| acc * x + coeff)
+    }
+
+    /// Evaluates this polynomial at each of `points`.
+    pub fn evaluate_many(&self, points: &[F]) -> Vec<F> {
+        let mut evals = points.to_vec();
+        parallelize(&mut evals, |chunk, _| {
+            for x in chunk {
+                *x = self.evaluate(*x);
+            }
+        });
+        evals
+    }
+
+    /// Divides this polynomial by $X - z$ with synthetic division, returning
+    /// the quotient and the remainder, which is the evaluation at `z`.
+    pub fn divide_by_linear(&self, z: F) -> (Self, F) {
+        if self.values.is_empty() {
+            return (Self::zero(), F::zero());
+        }
+
+        let mut quotient = vec![F::zero(); self.values.len() - 1];
+        let mut acc = F::zero();
+        for (i, coeff) in self.values.iter().enumerate().rev() {
+            acc = acc * z + coeff;
+            if i > 0 {
+                quotient[i - 1] = acc;
+            }
+        }
+
+        (Self::new(quotient), acc)
+    }
+
+    /// Returns the polynomial of least degree passing through `points`.
+    ///
+    /// This function will panic if two points share the same $x$.
+    pub fn interpolate(points: &[(F, F)]) -> Self {
+        // Compute the product of all X - x_j
+        let mut product = vec![F::one()];
+        for (x, _) in points {
+            product.insert(0, F::zero());
+            for i in 0..product.len() - 1 {
+                let tmp = product[i + 1] * x;
+                product[i] -= tmp;
+            }
+        }
+        let product = Self::new(product);
+
+        let mut res = vec![F::zero(); points.len()];
+        for (i, (x_i, y_i)) in points.iter().enumerate() {
+            // The i-th Lagrange basis polynomial, up to its denominator
+            let (basis, _) = product.divide_by_linear(*x_i);
+            let denominator = points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(F::one(), |acc, (_, (x_j, _))| acc * (*x_i - x_j));
+            let scale = *y_i * denominator.invert().unwrap();
+
+            for (r, b) in res.iter_mut().zip(basis.values.iter()) {
+                *r += *b * scale;
+            }
+        }
+
+        Self::new(res)
+    }
+
+    /// Evaluates this polynomial over the smallest domain of power of two size
+    /// holding its coefficients.
+    pub fn into_evaluations(self) -> Polynomial<F, LagrangeCoeff> {
+        let mut domain = EvaluationDomain::from_coeffs(self.values)
+            .expect("polynomial is too large for the field's 2-adicity");
+        domain.fft();
+        Polynomial::new(domain.into_coeffs())
+    }
+}
+
+impl<F: FieldExt> Polynomial<F, LagrangeCoeff> {
+    /// Creates a polynomial from its evaluations over the powers of the
+    /// primitive $n$-th root of unity, where $n$ is a power of two.
+    pub fn from_evals(evals: Vec<F>) -> Self {
+        assert!(evals.len().is_power_of_two());
+        Self::new(evals)
+    }
+
+    /// Interpolates the coefficients of this polynomial.
+    pub fn into_coeffs(self) -> Polynomial<F, Coeff> {
+        let mut domain = EvaluationDomain::from_coeffs(self.values).unwrap();
+        domain.ifft();
+        Polynomial::new(domain.into_coeffs())
+    }
+}
+
+impl<F: FieldExt> Add for &Polynomial<F, Coeff> {
+    type Output = Polynomial<F, Coeff>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (long, short) = if self.len() >= rhs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut values = long.values.clone();
+        for (v, b) in values.iter_mut().zip(short.values.iter()) {
+            *v += b;
+        }
+        Polynomial::new(values)
+    }
+}
+
+impl<F: FieldExt> Sub for &Polynomial<F, Coeff> {
+    type Output = Polynomial<F, Coeff>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut values = self.values.clone();
+        if values.len() < rhs.len() {
+            values.resize(rhs.len(), F::zero());
+        }
+        for (v, b) in values.iter_mut().zip(rhs.values.iter()) {
+            *v -= b;
+        }
+        Polynomial::new(values)
+    }
+}
+
+impl<F: FieldExt> Mul for &Polynomial<F, Coeff> {
+    type Output = Polynomial<F, Coeff>;
+
+    /// Multiplies two polynomials, through the FFT unless one of them is
+    /// small.
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_empty() || rhs.is_empty() {
+            return Polynomial::zero();
+        }
+
+        let len = self.len() + rhs.len() - 1;
+        if self.len().min(rhs.len()) <= 32 {
+            let mut values = vec![F::zero(); len];
+            for (i, a) in self.values.iter().enumerate() {
+                for (j, b) in rhs.values.iter().enumerate() {
+                    values[i + j] += *a * b;
+                }
+            }
+            return Polynomial::new(values);
+        }
+
+        let n = len.next_power_of_two();
+        let mut a = self.values.clone();
+        let mut b = rhs.values.clone();
+        a.resize(n, F::zero());
+        b.resize(n, F::zero());
+
+        let product = &Polynomial::<F, Coeff>::new(a).into_evaluations()
+            * &Polynomial::<F, Coeff>::new(b).into_evaluations();
+        let mut values = product.into_coeffs().values;
+        values.truncate(len);
+        Polynomial::new(values)
+    }
+}
+
+impl<F: FieldExt> Add for &Polynomial<F, LagrangeCoeff> {
+    type Output = Polynomial<F, LagrangeCoeff>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.len(), rhs.len());
+        let mut values = self.values.clone();
+        for (v, b) in values.iter_mut().zip(rhs.values.iter()) {
+            *v += b;
+        }
+        Polynomial::new(values)
+    }
+}
+
+impl<F: FieldExt> Sub for &Polynomial<F, LagrangeCoeff> {
+    type Output = Polynomial<F, LagrangeCoeff>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.len(), rhs.len());
+        let mut values = self.values.clone();
+        for (v, b) in values.iter_mut().zip(rhs.values.iter()) {
+            *v -= b;
+        }
+        Polynomial::new(values)
+    }
+}
+
+impl<F: FieldExt> Mul for &Polynomial<F, LagrangeCoeff> {
+    type Output = Polynomial<F, LagrangeCoeff>;
+
+    /// Multiplies two polynomials evaluated over the same domain pointwise.
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.len(), rhs.len());
+        let mut values = self.values.clone();
+        parallelize(&mut values, |chunk, start| {
+            for (v, b) in chunk.iter_mut().zip(rhs.values[start..].iter()) {
+                *v *= b;
+            }
+        });
+        Polynomial::new(values)
+    }
+}
+
+#[cfg(test)]
+use crate::bn256::Fr;
+#[cfg(test)]
+use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_polynomial_arithmetic() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let mut random_poly =
+        |n: usize| Polynomial::<Fr>::from_coeffs((0..n).map(|_| Fr::random(&mut rng)).collect());
+
+    for (n, m) in [(0, 3), (1, 1), (5, 40), (100, 70), (33, 200)] {
+        let a = random_poly(n);
+        let b = random_poly(m);
+        let x = Fr::from(0x1234_5678u64);
+
+        assert_eq!((&a + &b).evaluate(x), a.evaluate(x) + b.evaluate(x));
+        assert_eq!((&a - &b).evaluate(x), a.evaluate(x) - b.evaluate(x));
+        assert_eq!((&a * &b).evaluate(x), a.evaluate(x) * b.evaluate(x));
+        assert_eq!(a.scale(x).evaluate(x), a.evaluate(x) * x);
+
+        if n > 0 && m > 0 {
+            assert_eq!((&a * &b).len(), n + m - 1);
+        }
+    }
+
+    let a = random_poly(17);
+    assert_eq!(a.degree(), 16);
+    assert_eq!(Polynomial::<Fr>::zero().degree(), 0);
+
+    let evals = a.clone().into_evaluations();
+    assert_eq!(evals.len(), 32);
+    let mut coeffs = evals.clone().into_coeffs().into_values();
+    coeffs.truncate(17);
+    assert_eq!(Polynomial::from_coeffs(coeffs), a);
+
+    let sum = &evals + &evals;
+    assert_eq!(sum, (&a + &a).into_evaluations());
+    let square = &evals * &evals;
+    for (s, e) in square.as_ref().iter().zip(evals.as_ref().iter()) {
+        assert_eq!(*s, e.square());
+    }
+    assert_eq!(&(&square - &evals) + &evals, square);
+}
+
+#[test]
+fn test_divide_and_interpolate() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let a = Polynomial::<Fr>::from_coeffs((0..20).map(|_| Fr::random(&mut rng)).collect());
+    let z = Fr::random(&mut rng);
+    let x = Fr::random(&mut rng);
+
+    // a(X) = q(X) (X - z) + a(z)
+    let (q, r) = a.divide_by_linear(z);
+    assert_eq!(r, a.evaluate(z));
+    assert_eq!(a.evaluate(x), q.evaluate(x) * (x - z) + r);
+
+    let points: Vec<Fr> = (0..20).map(|_| Fr::random(&mut rng)).collect();
+    let evals = a.evaluate_many(&points);
+    for (p, e) in points.iter().zip(evals.iter()) {
+        assert_eq!(a.evaluate(*p), *e);
+    }
+
+    let points: Vec<(Fr, Fr)> = points.into_iter().zip(evals.into_iter()).collect();
+    assert_eq!(Polynomial::interpolate(&points), a);
+    assert_eq!(
+        Polynomial::<Fr>::interpolate(&points[..1]).into_values(),
+        vec![points[0].1]
+    );
+}
@@ -0,0 +1,199 @@
+//! This module implements constant-time modular inversion for 4-limb prime
+//! fields with the safegcd algorithm of Bernstein and Yang,
+//! https://eprint.iacr.org/2019/266.pdf
+//!
+//! The divsteps are processed in batches of 62, following the layout used by
+//! libsecp256k1: integers are kept in a signed base-$2^{62}$ representation
+//! with five limbs, where the four lower limbs lie in $[0, 2^{62})$ and the
+//! top limb is signed.
+
+/// The mask of a 62-bit limb.
+const M62: u64 = u64::MAX >> 2;
+
+/// The number of batches of 62 divsteps. By the bound of Bernstein and Yang,
+/// $\lfloor (49 \cdot 256 + 57) / 17 \rfloor = 741 \le 12 \cdot 62$ divsteps
+/// are enough to bring $g$ to zero for any 256-bit inputs.
+const BATCHES: usize = 12;
+
+/// A 2x2 transition matrix $[[u, v], [q, r]]$, scaled by $2^{62}$.
+type Transition = (i64, i64, i64, i64);
+
+/// Converts a little-endian 256-bit integer into five 62-bit limbs.
+const fn to_signed62(a: &[u64; 4]) -> [i64; 5] {
+    [
+        (a[0] & M62) as i64,
+        ((a[0] >> 62 | a[1] << 2) & M62) as i64,
+        ((a[1] >> 60 | a[2] << 4) & M62) as i64,
+        ((a[2] >> 58 | a[3] << 6) & M62) as i64,
+        (a[3] >> 56) as i64,
+    ]
+}
+
+/// Converts five non-negative 62-bit limbs back into a 256-bit integer.
+const fn from_signed62(a: &[i64; 5]) -> [u64; 4] {
+    let a = [
+        a[0] as u64,
+        a[1] as u64,
+        a[2] as u64,
+        a[3] as u64,
+        a[4] as u64,
+    ];
+    [
+        a[0] | a[1] << 62,
+        a[1] >> 2 | a[2] << 60,
+        a[2] >> 4 | a[3] << 58,
+        a[3] >> 6 | a[4] << 56,
+    ]
+}
+
+/// Performs 62 divsteps on the low limbs of $f$ and $g$ in constant time,
+/// returning the new $\eta = -\delta$ and the transition matrix.
+fn divsteps_62(mut eta: i64, f0: u64, g0: u64) -> (i64, Transition) {
+    let (mut u, mut v, mut q, mut r) = (1u64, 0u64, 0u64, 1u64);
+    let (mut f, mut g) = (f0, g0);
+
+    for _ in 0..62 {
+        // c1 is set if eta < 0, c2 if g is odd
+        let c1 = (eta >> 63) as u64;
+        let c2 = (g & 1).wrapping_neg();
+
+        // g += f, or g -= f if eta < 0, when g is odd
+        let x = (f ^ c1).wrapping_sub(c1);
+        let y = (u ^ c1).wrapping_sub(c1);
+        let z = (v ^ c1).wrapping_sub(c1);
+        g = g.wrapping_add(x & c2);
+        q = q.wrapping_add(y & c2);
+        r = r.wrapping_add(z & c2);
+
+        // If both eta < 0 and g is odd, (f, g) = (g, g - f) and eta = -eta,
+        // otherwise eta is decremented
+        let c1 = c1 & c2;
+        eta = (eta ^ c1 as i64) - (c1 as i64 + 1);
+        f = f.wrapping_add(g & c1);
+        u = u.wrapping_add(q & c1);
+        v = v.wrapping_add(r & c1);
+
+        // Halving g is accounted for by doubling the first row instead
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+    }
+
+    (eta, (u as i64, v as i64, q as i64, r as i64))
+}
+
+/// Computes $(f, g) = t \cdot (f, g) / 2^{62}$, which is exact.
+fn update_fg_62(f: &mut [i64; 5], g: &mut [i64; 5], t: Transition) {
+    let (u, v, q, r) = (t.0 as i128, t.1 as i128, t.2 as i128, t.3 as i128);
+
+    let mut cf = u * f[0] as i128 + v * g[0] as i128;
+    let mut cg = q * f[0] as i128 + r * g[0] as i128;
+    cf >>= 62;
+    cg >>= 62;
+
+    for i in 1..5 {
+        cf += u * f[i] as i128 + v * g[i] as i128;
+        cg += q * f[i] as i128 + r * g[i] as i128;
+        f[i - 1] = (cf as u64 & M62) as i64;
+        g[i - 1] = (cg as u64 & M62) as i64;
+        cf >>= 62;
+        cg >>= 62;
+    }
+
+    f[4] = cf as i64;
+    g[4] = cg as i64;
+}
+
+/// Computes $(d, e) = t \cdot (d, e) / 2^{62} \bmod m$, adding multiples of
+/// the modulus so that the division is exact. Both $d$ and $e$ are kept in
+/// the range $(-2m, m)$.
+fn update_de_62(d: &mut [i64; 5], e: &mut [i64; 5], t: Transition, m: &[i64; 5], m_inv: u64) {
+    let (u, v, q, r) = t;
+
+    // Start from [u, q] if d is negative and add [v, r] if e is negative,
+    // which brings the result back into range
+    let sd = d[4] >> 63;
+    let se = e[4] >> 63;
+    let mut md = (u & sd) + (v & se);
+    let mut me = (q & sd) + (r & se);
+
+    let (u, v, q, r) = (u as i128, v as i128, q as i128, r as i128);
+    let mut cd = u * d[0] as i128 + v * e[0] as i128;
+    let mut ce = q * d[0] as i128 + r * e[0] as i128;
+
+    // Correct md and me so that the lowest 62 bits of
+    // t * (d, e) + m * (md, me) are zero
+    md -= (m_inv.wrapping_mul(cd as u64).wrapping_add(md as u64) & M62) as i64;
+    me -= (m_inv.wrapping_mul(ce as u64).wrapping_add(me as u64) & M62) as i64;
+
+    cd += m[0] as i128 * md as i128;
+    ce += m[0] as i128 * me as i128;
+    cd >>= 62;
+    ce >>= 62;
+
+    for i in 1..5 {
+        cd += u * d[i] as i128 + v * e[i] as i128 + m[i] as i128 * md as i128;
+        ce += q * d[i] as i128 + r * e[i] as i128 + m[i] as i128 * me as i128;
+        d[i - 1] = (cd as u64 & M62) as i64;
+        e[i - 1] = (ce as u64 & M62) as i64;
+        cd >>= 62;
+        ce >>= 62;
+    }
+
+    d[4] = cd as i64;
+    e[4] = ce as i64;
+}
+
+/// Brings $r \in (-2m, m)$ into $[0, m)$, negating it first if `sign` is
+/// negative.
+fn normalize_62(r: &mut [i64; 5], sign: i64, m: &[i64; 5]) {
+    // Add the modulus if r is negative, then negate if requested
+    let cond_add = r[4] >> 63;
+    let cond_negate = sign >> 63;
+    for (r, m) in r.iter_mut().zip(m.iter()) {
+        *r += m & cond_add;
+        *r = (*r ^ cond_negate) - cond_negate;
+    }
+    carry_62(r);
+
+    // Add the modulus again if r is still negative
+    let cond_add = r[4] >> 63;
+    for (r, m) in r.iter_mut().zip(m.iter()) {
+        *r += m & cond_add;
+    }
+    carry_62(r);
+}
+
+/// Propagates the carries of the lower limbs into the top limb.
+fn carry_62(r: &mut [i64; 5]) {
+    for i in 0..4 {
+        r[i + 1] += r[i] >> 62;
+        r[i] &= M62 as i64;
+    }
+}
+
+/// Computes the inverse of `value` modulo the odd `modulus` in constant time,
+/// where `inv = -(modulus^{-1} mod 2^64) mod 2^64` is the Montgomery constant
+/// of the field. The input must be reduced, and zero is mapped to zero.
+pub(crate) fn safegcd_invert(value: &[u64; 4], modulus: &[u64; 4], inv: u64) -> [u64; 4] {
+    let m = to_signed62(modulus);
+    let m_inv = inv.wrapping_neg() & M62;
+
+    // Maintain f = d * value and g = e * value modulo the modulus
+    let mut d = [0i64; 5];
+    let mut e = [1i64, 0, 0, 0, 0];
+    let mut f = m;
+    let mut g = to_signed62(value);
+    let mut eta = -1;
+
+    for _ in 0..BATCHES {
+        let (next, t) = divsteps_62(eta, f[0] as u64, g[0] as u64);
+        eta = next;
+        update_de_62(&mut d, &mut e, t, &m, m_inv);
+        update_fg_62(&mut f, &mut g, t);
+    }
+
+    // Now g is zero and f is the gcd, +1 or -1
+    normalize_62(&mut d, f[4], &m);
+    from_signed62(&d)
+}
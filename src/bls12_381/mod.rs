@@ -2,9 +2,21 @@ use crate::arithmetic::BaseExt;
 use crate::arithmetic::Coordinates;
 use crate::arithmetic::CurveAffine;
 use crate::arithmetic::CurveExt;
+use crate::arithmetic::CurveHasher;
+use crate::arithmetic::Engine;
 use crate::arithmetic::FieldExt;
 use crate::arithmetic::Group;
-use ff::PrimeField;
+use crate::arithmetic::HashToCurve;
+use crate::arithmetic::MultiMillerLoop;
+use crate::arithmetic::PairingCurveAffine;
+use crate::hash_to_curve::expand_message_xmd;
+use bls12_381::hash_to_curve::{ExpandMessageState, InitExpandMessage};
+use core::cmp::Ordering;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::{Field, PrimeField};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use rand_core::RngCore;
 
 pub use bls12_381::Fp as Fq;
 pub use bls12_381::Fp2;
@@ -15,8 +27,145 @@ pub use bls12_381::{
     multi_miller_loop, pairing, Bls12, G1Affine, G2Affine, G2Prepared, Gt, MillerLoopResult,
 };
 use subtle::Choice;
+use subtle::ConditionallySelectable;
+use subtle::ConstantTimeEq;
 use subtle::CtOption;
 
+/// The quadratic extension of `Fq`, over which G2 is defined. It wraps
+/// `Fp2` to implement the field traits of this crate.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fq2(pub Fp2);
+
+impl Ord for Fq2 {
+    fn cmp(&self, other: &Fq2) -> Ordering {
+        match self.0.c1.cmp(&other.0.c1) {
+            Ordering::Equal => self.0.c0.cmp(&other.0.c0),
+            ordering => ordering,
+        }
+    }
+}
+
+impl PartialOrd for Fq2 {
+    fn partial_cmp(&self, other: &Fq2) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ConditionallySelectable for Fq2 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fq2(Fp2::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl ConstantTimeEq for Fq2 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<'a> Neg for &'a Fq2 {
+    type Output = Fq2;
+
+    fn neg(self) -> Fq2 {
+        Fq2(-self.0)
+    }
+}
+
+impl Neg for Fq2 {
+    type Output = Fq2;
+
+    fn neg(self) -> Fq2 {
+        -&self
+    }
+}
+
+impl<'a, 'b> Add<&'b Fq2> for &'a Fq2 {
+    type Output = Fq2;
+
+    fn add(self, rhs: &'b Fq2) -> Fq2 {
+        Fq2(self.0 + rhs.0)
+    }
+}
+
+impl<'a, 'b> Sub<&'b Fq2> for &'a Fq2 {
+    type Output = Fq2;
+
+    fn sub(self, rhs: &'b Fq2) -> Fq2 {
+        Fq2(self.0 - rhs.0)
+    }
+}
+
+impl<'a, 'b> Mul<&'b Fq2> for &'a Fq2 {
+    type Output = Fq2;
+
+    fn mul(self, rhs: &'b Fq2) -> Fq2 {
+        Fq2(self.0 * rhs.0)
+    }
+}
+
+impl_binops_additive!(Fq2, Fq2);
+impl_binops_multiplicative!(Fq2, Fq2);
+
+impl Field for Fq2 {
+    fn random(mut rng: impl RngCore) -> Self {
+        Fq2(Fp2 {
+            c0: Fq::random(&mut rng),
+            c1: Fq::random(&mut rng),
+        })
+    }
+
+    fn zero() -> Self {
+        Fq2(Fp2::zero())
+    }
+
+    fn one() -> Self {
+        Fq2(Fp2::one())
+    }
+
+    fn is_zero(&self) -> Choice {
+        self.0.is_zero()
+    }
+
+    fn square(&self) -> Self {
+        Fq2(self.0.square())
+    }
+
+    fn double(&self) -> Self {
+        Fq2(self.0 + self.0)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        self.0.sqrt().map(Fq2)
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        self.0.invert().map(Fq2)
+    }
+}
+
+impl BaseExt for Fq2 {
+    const MODULUS: &'static str = Fq::MODULUS;
+
+    /// Reduces the little endian integer into `Fq`, which embeds in `Fq2`.
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        Fq2(Fp2 {
+            c0: Fq::from_bytes_wide(bytes),
+            c1: Fq::zero(),
+        })
+    }
+
+    fn write<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.0.c0.write(writer)?;
+        self.0.c1.write(writer)
+    }
+
+    fn read<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let c0 = Fq::read(reader)?;
+        let c1 = Fq::read(reader)?;
+        Ok(Fq2(Fp2 { c0, c1 }))
+    }
+}
+
 impl BaseExt for Fr {
     const MODULUS: &'static str =
         "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
@@ -42,8 +191,17 @@ impl BaseExt for Fr {
 impl BaseExt for Fq {
     const MODULUS: &'static str = "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
 
-    fn from_bytes_wide(_bytes: &[u8; 64]) -> Self {
-        unimplemented!()
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        // The integer is lo + hi * 2^256 with both halves below the modulus
+        let half = |bytes: &[u8]| {
+            let mut repr = [0u8; 48];
+            repr[16..].copy_from_slice(bytes);
+            repr[16..].reverse();
+            Fq::from_bytes(&repr).unwrap()
+        };
+        let mut two_256 = [0u8; 48];
+        two_256[15] = 1;
+        half(&bytes[..32]) + half(&bytes[32..]) * Fq::from_bytes(&two_256).unwrap()
     }
 
     fn write<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
@@ -220,3 +378,469 @@ impl CurveAffine for G1Affine {
         todo!()
     }
 }
+
+impl Group for G2 {
+    type Scalar = Fr;
+
+    fn group_zero() -> Self {
+        G2::identity()
+    }
+
+    fn group_add(&mut self, rhs: &Self) {
+        *self = &*self + rhs
+    }
+
+    fn group_sub(&mut self, rhs: &Self) {
+        *self = &*self - rhs
+    }
+
+    fn group_scale(&mut self, by: &Self::Scalar) {
+        *self = &*self * by
+    }
+}
+
+/// Returns the constant $b = 4(u + 1)$ of G2.
+fn g2_b() -> Fq2 {
+    let two = Fq::one() + Fq::one();
+    let four = two + two;
+    Fq2(Fp2 { c0: four, c1: four })
+}
+
+// 1 / 2^((q - 1) / 3), by which psi^2 multiplies x
+const PSI2_COEFF_X: Fq2 = Fq2(Fp2 {
+    c0: Fq::from_raw_unchecked([
+        0xcd03c9e48671f071,
+        0x5dab22461fcda5d2,
+        0x587042afd3851b95,
+        0x8eb60ebe01bacb9e,
+        0x03f97d6e83d050d2,
+        0x18f0206554638741,
+    ]),
+    c1: Fq::zero(),
+});
+
+// x^2 for the curve parameter x = -0xd201000000010000, the eigenvalue of
+// psi^2 on G2
+const PSI2_EIGENVALUE: u128 = 0xd201000000010000 * 0xd201000000010000;
+
+impl CurveExt for G2 {
+    type ScalarExt = Fr;
+
+    type Base = Fq2;
+
+    type AffineExt = G2Affine;
+
+    const CURVE_ID: &'static str = "bls12_381_g2";
+
+    fn jacobian_coordinates(&self) -> (Self::Base, Self::Base, Self::Base) {
+        // bls12_381 keeps homogeneous coordinates, so go through the affine
+        // point, with z = 0 for the identity
+        match Option::<Coordinates<G2Affine>>::from(G2Affine::from(self).coordinates()) {
+            Some(coordinates) => (*coordinates.x(), *coordinates.y(), Fq2::one()),
+            None => (Fq2::zero(), Fq2::one(), Fq2::zero()),
+        }
+    }
+
+    fn is_on_curve(&self) -> Choice {
+        self.is_on_curve()
+    }
+
+    fn b() -> Self::Base {
+        g2_b()
+    }
+
+    fn new_jacobian(x: Self::Base, y: Self::Base, z: Self::Base) -> CtOption<Self> {
+        if bool::from(z.is_zero()) {
+            return CtOption::new(G2::identity(), Choice::from(1u8));
+        }
+        let z_inv = z.invert().unwrap();
+        let z_inv2 = z_inv.square();
+        G2Affine::from_xy(x * z_inv2, y * z_inv2 * z_inv).map(G2::from)
+    }
+}
+
+impl CurveAffine for G2Affine {
+    type ScalarExt = Fr;
+
+    type Base = Fq2;
+
+    type CurveExt = G2;
+
+    fn is_on_curve(&self) -> Choice {
+        self.is_on_curve()
+    }
+
+    fn coordinates(&self) -> CtOption<Coordinates<Self>> {
+        // The coordinates are only reachable through the uncompressed
+        // encoding, x.c1 || x.c0 || y.c1 || y.c0 in big endian
+        let bytes = self.to_uncompressed();
+        let fq = |i: usize| {
+            let mut repr = [0u8; 48];
+            repr.copy_from_slice(&bytes[48 * i..48 * (i + 1)]);
+            repr[0] &= 0x1f;
+            Fq::from_bytes(&repr).unwrap()
+        };
+        CtOption::new(
+            Coordinates {
+                x: Fq2(Fp2 {
+                    c0: fq(1),
+                    c1: fq(0),
+                }),
+                y: Fq2(Fp2 {
+                    c0: fq(3),
+                    c1: fq(2),
+                }),
+            },
+            !self.is_identity(),
+        )
+    }
+
+    fn from_xy(x: Self::Base, y: Self::Base) -> CtOption<Self> {
+        if x == Fq2::zero() && y == Fq2::one() {
+            return CtOption::new(Self::identity(), Choice::from(1u8));
+        }
+        let mut bytes = [0u8; 192];
+        for (i, fq) in [x.0.c1, x.0.c0, y.0.c1, y.0.c0].iter().enumerate() {
+            bytes[48 * i..48 * (i + 1)].copy_from_slice(&fq.to_bytes());
+        }
+        G2Affine::from_uncompressed_unchecked(&bytes)
+            .and_then(|p| CtOption::new(p, p.is_on_curve()))
+    }
+
+    fn b() -> Self::Base {
+        g2_b()
+    }
+
+    /// Returns $\psi^2(P)$, which is $[x^2] P$ on G2.
+    fn get_endomorphism_base(base: &Self) -> Self {
+        match Option::<Coordinates<Self>>::from(base.coordinates()) {
+            Some(coordinates) => {
+                Self::from_xy(*coordinates.x() * PSI2_COEFF_X, -*coordinates.y()).unwrap()
+            }
+            None => *base,
+        }
+    }
+
+    /// Returns $(k_1, k_2)$ with $k = k_1 + k_2 x^2$, so that
+    /// $[k] P = [k_1] P + [k_2] \psi^2(P)$.
+    fn get_endomorphism_scalars(k: &Self::ScalarExt) -> (u128, u128) {
+        // The order is below 2^255, so both quotient and remainder by x^2
+        // fit in 128 bits
+        let k = BigUint::from_bytes_le(&k.to_bytes());
+        let eigenvalue = BigUint::from(PSI2_EIGENVALUE);
+        (
+            (&k % &eigenvalue).to_u128().unwrap(),
+            (&k / &eigenvalue).to_u128().unwrap(),
+        )
+    }
+
+    /// The coordinates of `bls12_381` points are private, so the sums are
+    /// computed in projective coordinates and normalized with one batched
+    /// inversion. Projective addition is complete, so `COMPLETE` makes no
+    /// difference.
+    fn batch_add<const COMPLETE: bool, const LOAD_POINTS: bool>(
+        points: &mut [Self],
+        output_indices: &[u32],
+        num_points: usize,
+        offset: usize,
+        bases: &[Self],
+        base_positions: &[u32],
+    ) {
+        let get_point = |point_data: u32| -> Self {
+            let negate = point_data & 0x80000000 != 0;
+            let base_idx = (point_data & 0x7FFFFFFF) as usize;
+            if negate {
+                -bases[base_idx]
+            } else {
+                bases[base_idx]
+            }
+        };
+
+        let sums: Vec<G2> = (0..num_points)
+            .step_by(2)
+            .map(|i| {
+                if LOAD_POINTS {
+                    points[i] = get_point(base_positions[i]);
+                    points[i + 1] = get_point(base_positions[i + 1]);
+                }
+                G2::from(points[i]) + points[i + 1]
+            })
+            .collect();
+        let mut affine = vec![Self::identity(); sums.len()];
+        G2::batch_normalize(&sums, &mut affine);
+
+        for (i, sum) in affine.into_iter().enumerate() {
+            points[output_indices[i] as usize - offset] = sum;
+        }
+    }
+}
+
+impl PairingCurveAffine for G1Affine {
+    type Pair = G2Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &Self::Pair) -> Self::PairingResult {
+        pairing(self, other)
+    }
+}
+
+impl PairingCurveAffine for G2Affine {
+    type Pair = G1Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &Self::Pair) -> Self::PairingResult {
+        pairing(other, self)
+    }
+}
+
+/// The value of a Miller loop of `Bls12`. It wraps `MillerLoopResult` to
+/// implement the Miller loop trait of this crate.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Bls12MillerLoopResult(pub MillerLoopResult);
+
+impl crate::arithmetic::MillerLoopResult for Bls12MillerLoopResult {
+    type Gt = Gt;
+
+    fn final_exponentiation(&self) -> Gt {
+        self.0.final_exponentiation()
+    }
+}
+
+impl Engine for Bls12 {
+    type Scalar = Fr;
+    type Fr = Fr;
+    type G1 = G1;
+    type G1Affine = G1Affine;
+    type G2 = G2;
+    type G2Affine = G2Affine;
+    type Gt = Gt;
+
+    fn pairing(p: &Self::G1Affine, q: &Self::G2Affine) -> Self::Gt {
+        pairing(p, q)
+    }
+}
+
+impl MultiMillerLoop for Bls12 {
+    type G2Prepared = G2Prepared;
+    type Result = Bls12MillerLoopResult;
+
+    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result {
+        Bls12MillerLoopResult(multi_miller_loop(terms))
+    }
+}
+
+/// `expand_message_xmd` with SHA-256, for the hash to curve maps of
+/// `bls12_381`.
+struct ExpandMsgXmdSha256;
+
+struct ExpandedMessage {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl<'x> InitExpandMessage<'x> for ExpandMsgXmdSha256 {
+    type Expander = ExpandedMessage;
+
+    fn init_expand(message: &[u8], dst: &'x [u8], len_in_bytes: usize) -> ExpandedMessage {
+        ExpandedMessage {
+            bytes: expand_message_xmd(message, dst, len_in_bytes),
+            offset: 0,
+        }
+    }
+}
+
+impl<'x> ExpandMessageState<'x> for ExpandedMessage {
+    fn read_into(&mut self, output: &mut [u8]) -> usize {
+        let len = output.len().min(self.remain());
+        output[..len].copy_from_slice(&self.bytes[self.offset..self.offset + len]);
+        self.offset += len;
+        len
+    }
+
+    fn remain(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+}
+
+macro_rules! impl_hash_to_curve {
+    ($name:ident, $suite_id:literal) => {
+        impl HashToCurve for $name {
+            const SUITE_ID: &'static str = $suite_id;
+
+            fn hash_to_curve(domain_prefix: &str) -> CurveHasher<'_, Self> {
+                let dst = format!("{}-{}_XMD:SHA-256_SSWU_RO_", domain_prefix, Self::CURVE_ID);
+                Box::new(move |message| {
+                    <$name as bls12_381::hash_to_curve::HashToCurve<ExpandMsgXmdSha256>>::hash_to_curve(
+                        message,
+                        dst.as_bytes(),
+                    )
+                })
+            }
+
+            fn hash_to_curve_with_dst(dst: &[u8]) -> CurveHasher<'_, Self> {
+                Box::new(move |message| {
+                    <$name as bls12_381::hash_to_curve::HashToCurve<ExpandMsgXmdSha256>>::hash_to_curve(
+                        message, dst,
+                    )
+                })
+            }
+        }
+    };
+}
+
+impl_hash_to_curve!(G1, "BLS12381G1_XMD:SHA-256_SSWU_RO_");
+impl_hash_to_curve!(G2, "BLS12381G2_XMD:SHA-256_SSWU_RO_");
+
+#[cfg(test)]
+use group::Curve;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_g2_coordinates() {
+    let p = (G2Affine::generator() * Fr::from(5)).to_affine();
+    let coordinates = p.coordinates().unwrap();
+    assert_eq!(
+        G2Affine::from_xy(*coordinates.x(), *coordinates.y()).unwrap(),
+        p
+    );
+    assert!(bool::from(
+        G2Affine::from_xy(*coordinates.x(), *coordinates.x()).is_none()
+    ));
+    assert!(bool::from(G2Affine::identity().coordinates().is_none()));
+
+    // y^2 = x^3 + b
+    let (x, y) = (*coordinates.x(), *coordinates.y());
+    assert_eq!(y.square(), x.square() * x + G2Affine::b());
+}
+
+#[test]
+fn test_multi_miller_loop() {
+    use crate::arithmetic::MillerLoopResult as _;
+
+    let p = (G1Affine::generator() * Fr::from(3)).to_affine();
+    let q = (G2Affine::generator() * Fr::from(5)).to_affine();
+    let q_prepared = G2Prepared::from(q);
+
+    // The Miller loop value is only turned into Gt by the final exponentiation
+    let f = Bls12::multi_miller_loop(&[(&p, &q_prepared)]);
+    assert_eq!(f.final_exponentiation(), pairing(&p, &q));
+
+    let neg_p = -p;
+    assert!(bool::from(Bls12::pairing_check(&[
+        (&p, &q_prepared),
+        (&neg_p, &q_prepared)
+    ])));
+    assert!(!bool::from(Bls12::pairing_check(&[(&p, &q_prepared)])));
+}
+
+#[test]
+fn test_from_bytes_wide() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let modulus = BigUint::parse_bytes(&Fq::MODULUS.as_bytes()[2..], 16).unwrap();
+    for _ in 0..10 {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        let mut expected = (BigUint::from_bytes_le(&bytes) % &modulus).to_bytes_be();
+        while expected.len() < 48 {
+            expected.insert(0, 0);
+        }
+        assert_eq!(Fq::from_bytes_wide(&bytes).to_bytes()[..], expected[..]);
+        assert_eq!(
+            Fq2::from_bytes_wide(&bytes),
+            Fq2(Fp2 {
+                c0: Fq::from_bytes_wide(&bytes),
+                c1: Fq::zero()
+            })
+        );
+    }
+}
+
+#[test]
+fn test_g2_jacobian_coordinates() {
+    let p = G2::generator() * Fr::from(7);
+    let (x, y, z) = p.jacobian_coordinates();
+    assert_eq!(G2::new_jacobian(x, y, z).unwrap(), p);
+
+    // Any representative of the same point is accepted
+    let t = Fq2::random(XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]));
+    let t2 = t.square();
+    assert_eq!(G2::new_jacobian(x * t2, y * t2 * t, z * t).unwrap(), p);
+    assert!(bool::from(G2::new_jacobian(x, x, z).is_none()));
+
+    let (x, y, z) = G2::identity().jacobian_coordinates();
+    assert_eq!(z, Fq2::zero());
+    assert_eq!(G2::new_jacobian(x, y, z).unwrap(), G2::identity());
+}
+
+#[test]
+fn test_g2_endomorphism() {
+    let fr = |v: u128| Fr::from_raw([v as u64, (v >> 64) as u64, 0, 0]);
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let p = (G2::generator() * Fr::random(&mut rng)).to_affine();
+    let endo = G2Affine::get_endomorphism_base(&p);
+    assert_eq!(endo, (p * fr(PSI2_EIGENVALUE)).to_affine());
+    assert_eq!(
+        G2Affine::get_endomorphism_base(&G2Affine::identity()),
+        G2Affine::identity()
+    );
+
+    for k in [Fr::zero(), Fr::one(), -Fr::one(), Fr::random(&mut rng)] {
+        let (k1, k2) = G2Affine::get_endomorphism_scalars(&k);
+        assert_eq!(p * fr(k1) + endo * fr(k2), p * k);
+    }
+}
+
+#[test]
+fn test_g2_batch_add() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let mut bases: Vec<_> = (0..4)
+        .map(|_| (G2::generator() * Fr::random(&mut rng)).to_affine())
+        .collect();
+    bases.push(G2Affine::identity());
+
+    // Pairs of bases, the second one negated for the flagged positions,
+    // including doublings, opposite points and the identity
+    let pairs = [
+        (0, 1),
+        (2, 2),
+        (3, 3 | 0x80000000),
+        (4, 1),
+        (0, 4 | 0x80000000),
+    ];
+    let base_positions: Vec<u32> = pairs.iter().flat_map(|&(a, b)| [a, b]).collect();
+    let offset = 3;
+    let output_indices: Vec<u32> = (0..pairs.len() as u32).map(|i| i + offset).collect();
+
+    let mut points = vec![G2Affine::identity(); 2 * pairs.len()];
+    G2Affine::batch_add::<true, true>(
+        &mut points,
+        &output_indices,
+        2 * pairs.len(),
+        offset as usize,
+        &bases,
+        &base_positions,
+    );
+    for (i, &(a, b)) in pairs.iter().enumerate() {
+        let b = if b & 0x80000000 != 0 {
+            -bases[(b & 0x7FFFFFFF) as usize]
+        } else {
+            bases[b as usize]
+        };
+        assert_eq!(points[i], (G2::from(bases[a as usize]) + b).to_affine());
+    }
+}
@@ -0,0 +1,603 @@
+//! This module implements BLS signatures with proofs of possession,
+//! following https://datatracker.ietf.org/doc/draft-irtf-cfrg-bls-signature/
+//!
+//! A signature on a message $m$ under the secret key $x$ is $[x] H(m)$,
+//! where $H$ hashes to the signature group, and is accepted for the public
+//! key $[x] g$ when $e(pk, H(m)) = e(g, \sigma)$. Public keys live in G1 and
+//! signatures in G2 with [`MinPk`], and the other way around with
+//! [`MinSig`]. The ciphersuites are the proof of possession ones of the
+//! draft: messages are hashed with the domain separation tag
+//! `BLS_SIG_<suite>POP_` and public keys proven with `BLS_POP_<suite>POP_`,
+//! where `<suite>` is the hash to curve suite of the signature group, so that
+//! a proof of possession is never a valid signature. For instance, signatures
+//! of `MinPk<Bls12>` use `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`, and
+//! those of `MinPk<Bn256>` use
+//! `BLS_SIG_BN254G2_XMD:SHA-256_SVDW-PAIRING-BN256_RO_POP_`.
+//!
+//! Aggregating public keys is only safe once each of them came with a valid
+//! proof of possession, which prevents rogue key attacks.
+//!
+//! The variants are available for engines whose signature group implements
+//! [`HashToCurve`], which is the case of BN254 and BLS12-381.
+
+use crate::arithmetic::{
+    is_torsion_free, BaseExt, CurveAffine, Engine, HashToCurve, MillerLoopResult, MultiMillerLoop,
+};
+use core::fmt;
+use core::marker::PhantomData;
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group, GroupEncoding};
+use hkdf::Hkdf;
+use rand_core::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::Choice;
+
+/// The assignment of public keys and signatures to the groups of a pairing.
+pub trait Variant {
+    /// The pairing engine.
+    type Engine: MultiMillerLoop;
+    /// The group of public keys.
+    type PublicKeyGroup: CurveAffine<ScalarExt = <Self::Engine as Engine>::Scalar>;
+    /// The group of signatures, which messages are hashed to.
+    type SignatureGroup: CurveAffine<ScalarExt = <Self::Engine as Engine>::Scalar>;
+
+    /// The hash to curve suite of the signature group.
+    const SUITE_ID: &'static str;
+
+    /// Hashes `message` to the signature group with the domain separation
+    /// tag `dst`.
+    fn hash_to_signature_group(dst: &[u8], message: &[u8]) -> Self::SignatureGroup;
+
+    /// Computes the Miller loop of the pairings $e(p_i, s_i)$ of the terms.
+    fn multi_miller_loop(
+        terms: &[(Self::PublicKeyGroup, Self::SignatureGroup)],
+    ) -> <Self::Engine as MultiMillerLoop>::Result;
+}
+
+/// Public keys in G1 and signatures in G2.
+pub struct MinPk<E>(PhantomData<E>);
+
+/// Public keys in G2 and signatures in G1.
+pub struct MinSig<E>(PhantomData<E>);
+
+impl<E: MultiMillerLoop> Variant for MinPk<E>
+where
+    E::G2: HashToCurve<AffineExt = E::G2Affine>,
+{
+    type Engine = E;
+    type PublicKeyGroup = E::G1Affine;
+    type SignatureGroup = E::G2Affine;
+
+    const SUITE_ID: &'static str = E::G2::SUITE_ID;
+
+    fn hash_to_signature_group(dst: &[u8], message: &[u8]) -> E::G2Affine {
+        E::G2::hash_to_curve_with_dst(dst)(message).to_affine()
+    }
+
+    fn multi_miller_loop(terms: &[(E::G1Affine, E::G2Affine)]) -> E::Result {
+        let prepared: Vec<_> = terms.iter().map(|(_, q)| E::G2Prepared::from(*q)).collect();
+        let terms: Vec<_> = terms
+            .iter()
+            .zip(prepared.iter())
+            .map(|((p, _), q)| (p, q))
+            .collect();
+        E::multi_miller_loop(&terms)
+    }
+}
+
+impl<E: MultiMillerLoop> Variant for MinSig<E>
+where
+    E::G1: HashToCurve<AffineExt = E::G1Affine>,
+{
+    type Engine = E;
+    type PublicKeyGroup = E::G2Affine;
+    type SignatureGroup = E::G1Affine;
+
+    const SUITE_ID: &'static str = E::G1::SUITE_ID;
+
+    fn hash_to_signature_group(dst: &[u8], message: &[u8]) -> E::G1Affine {
+        E::G1::hash_to_curve_with_dst(dst)(message).to_affine()
+    }
+
+    fn multi_miller_loop(terms: &[(E::G2Affine, E::G1Affine)]) -> E::Result {
+        let prepared: Vec<_> = terms.iter().map(|(q, _)| E::G2Prepared::from(*q)).collect();
+        let terms: Vec<_> = terms
+            .iter()
+            .zip(prepared.iter())
+            .map(|((_, p), q)| (p, q))
+            .collect();
+        E::multi_miller_loop(&terms)
+    }
+}
+
+/// Returns the domain separation tag of the signatures of `V`.
+fn signature_dst<V: Variant>() -> String {
+    format!("BLS_SIG_{}POP_", V::SUITE_ID)
+}
+
+/// Returns the domain separation tag of the proofs of possession of `V`.
+fn pop_dst<V: Variant>() -> String {
+    format!("BLS_POP_{}POP_", V::SUITE_ID)
+}
+
+/// A secret key.
+pub struct SecretKey<V: Variant>(<V::Engine as Engine>::Scalar);
+
+/// A public key, which should only be trusted once its proof of possession
+/// is verified.
+pub struct PublicKey<V: Variant>(pub V::PublicKeyGroup);
+
+/// A signature, or an aggregate of signatures.
+pub struct Signature<V: Variant>(pub V::SignatureGroup);
+
+impl<V: Variant> SecretKey<V> {
+    /// Samples a secret key from `rng`.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let x = <V::Engine as Engine>::Scalar::random(&mut rng);
+            if !bool::from(x.is_zero()) {
+                return SecretKey(x);
+            }
+        }
+    }
+
+    /// Derives a secret key from the secret `ikm` of at least 32 bytes with
+    /// the `KeyGen` procedure of the draft, with an empty `key_info`.
+    ///
+    /// This function will panic if `ikm` is shorter than 32 bytes.
+    pub fn key_gen(ikm: &[u8]) -> Self {
+        assert!(ikm.len() >= 32);
+        // L = ceil((3 * ceil(log2(r))) / 16), which is 48 for both the
+        // 254 bit r of BN254 and the 255 bit r of BLS12-381
+        const L: usize = 48;
+
+        let mut input = ikm.to_vec();
+        input.push(0);
+        let mut salt = Sha256::digest(b"BLS-SIG-KEYGEN-SALT-");
+        loop {
+            let mut okm = [0u8; L];
+            Hkdf::<Sha256>::new(Some(salt.as_slice()), &input)
+                .expand(&(L as u16).to_be_bytes(), &mut okm)
+                .unwrap();
+
+            let mut wide = [0u8; 64];
+            for (w, b) in wide.iter_mut().zip(okm.iter().rev()) {
+                *w = *b;
+            }
+            let x = <V::Engine as Engine>::Scalar::from_bytes_wide(&wide);
+            if !bool::from(x.is_zero()) {
+                return SecretKey(x);
+            }
+            salt = Sha256::digest(salt.as_slice());
+        }
+    }
+
+    /// Returns the public key $[x] g$.
+    pub fn public_key(&self) -> PublicKey<V> {
+        PublicKey((V::PublicKeyGroup::generator() * self.0).to_affine())
+    }
+
+    /// Signs `message`.
+    pub fn sign(&self, message: &[u8]) -> Signature<V> {
+        let dst = signature_dst::<V>();
+        Signature((V::hash_to_signature_group(dst.as_bytes(), message) * self.0).to_affine())
+    }
+
+    /// Proves the possession of this key by signing the public key with a
+    /// separate domain.
+    pub fn pop_prove(&self) -> Signature<V> {
+        let public_key = self.public_key().0.to_bytes();
+        let dst = pop_dst::<V>();
+        Signature(
+            (V::hash_to_signature_group(dst.as_bytes(), public_key.as_ref()) * self.0).to_affine(),
+        )
+    }
+}
+
+impl<V: Variant> PublicKey<V> {
+    /// Checks that this is a valid public key, in the prime order subgroup
+    /// and not the identity.
+    pub fn validate(&self) -> Choice {
+        Choice::from((!bool::from(self.0.is_identity()) && is_torsion_free(&self.0)) as u8)
+    }
+
+    /// Verifies `signature` on `message`.
+    pub fn verify(&self, message: &[u8], signature: &Signature<V>) -> Choice {
+        if !bool::from(self.validate()) {
+            return Choice::from(0);
+        }
+        let dst = signature_dst::<V>();
+        core_verify(
+            &[(self.0, V::hash_to_signature_group(dst.as_bytes(), message))],
+            signature,
+        )
+    }
+
+    /// Verifies the proof of possession of this key.
+    pub fn pop_verify(&self, proof: &Signature<V>) -> Choice {
+        if !bool::from(self.validate()) {
+            return Choice::from(0);
+        }
+        let public_key = self.0.to_bytes();
+        let dst = pop_dst::<V>();
+        core_verify(
+            &[(
+                self.0,
+                V::hash_to_signature_group(dst.as_bytes(), public_key.as_ref()),
+            )],
+            proof,
+        )
+    }
+
+    /// Aggregates public keys into a key verifying the aggregate of their
+    /// signatures on a common message.
+    pub fn aggregate(public_keys: &[PublicKey<V>]) -> Self {
+        let sum = public_keys.iter().fold(
+            <V::PublicKeyGroup as CurveAffine>::CurveExt::identity(),
+            |acc, public_key| acc + public_key.0,
+        );
+        PublicKey(sum.to_affine())
+    }
+}
+
+impl<V: Variant> Signature<V> {
+    /// Aggregates signatures, on the same or on distinct messages.
+    pub fn aggregate(signatures: &[Signature<V>]) -> Self {
+        let sum = signatures.iter().fold(
+            <V::SignatureGroup as CurveAffine>::CurveExt::identity(),
+            |acc, signature| acc + signature.0,
+        );
+        Signature(sum.to_affine())
+    }
+}
+
+/// Checks $\prod_i e(p_i, s_i) = e(g, \sigma)$ with a single multi Miller
+/// loop.
+fn core_verify<V: Variant>(
+    terms: &[(V::PublicKeyGroup, V::SignatureGroup)],
+    signature: &Signature<V>,
+) -> Choice {
+    if !is_torsion_free(&signature.0) {
+        return Choice::from(0);
+    }
+    let mut terms = terms.to_vec();
+    terms.push((-V::PublicKeyGroup::generator(), signature.0));
+    V::multi_miller_loop(&terms).is_one_after_final_exponentiation()
+}
+
+/// Verifies the aggregate `signature` of the signatures of `messages[i]`
+/// under `public_keys[i]`. The messages must be distinct, which is what
+/// makes this safe without proofs of possession.
+pub fn aggregate_verify<V: Variant>(
+    public_keys: &[PublicKey<V>],
+    messages: &[&[u8]],
+    signature: &Signature<V>,
+) -> Choice {
+    if public_keys.is_empty() || public_keys.len() != messages.len() {
+        return Choice::from(0);
+    }
+    let mut sorted = messages.to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Choice::from(0);
+    }
+    if public_keys
+        .iter()
+        .any(|public_key| !bool::from(public_key.validate()))
+    {
+        return Choice::from(0);
+    }
+
+    let dst = signature_dst::<V>();
+    let terms: Vec<_> = public_keys
+        .iter()
+        .zip(messages.iter())
+        .map(|(public_key, message)| {
+            (
+                public_key.0,
+                V::hash_to_signature_group(dst.as_bytes(), message),
+            )
+        })
+        .collect();
+    core_verify(&terms, signature)
+}
+
+/// Verifies the aggregate `signature` of signatures of the same `message`
+/// under `public_keys`, whose proofs of possession must have been verified.
+pub fn fast_aggregate_verify<V: Variant>(
+    public_keys: &[PublicKey<V>],
+    message: &[u8],
+    signature: &Signature<V>,
+) -> Choice {
+    if public_keys.is_empty() {
+        return Choice::from(0);
+    }
+    PublicKey::aggregate(public_keys).verify(message, signature)
+}
+
+impl<V: Variant> Clone for PublicKey<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: Variant> Copy for PublicKey<V> {}
+
+impl<V: Variant> fmt::Debug for PublicKey<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PublicKey").field(&self.0).finish()
+    }
+}
+
+impl<V: Variant> PartialEq for PublicKey<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<V: Variant> Eq for PublicKey<V> {}
+
+impl<V: Variant> Clone for Signature<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: Variant> Copy for Signature<V> {}
+
+impl<V: Variant> fmt::Debug for Signature<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Signature").field(&self.0).finish()
+    }
+}
+
+impl<V: Variant> PartialEq for Signature<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<V: Variant> Eq for Signature<V> {}
+
+#[cfg(test)]
+use crate::bls12_381::{Bls12, Fr as Bls12Fr};
+#[cfg(test)]
+use crate::bn256::Bn256;
+#[cfg(test)]
+use ff::PrimeField;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[cfg(test)]
+fn test_sign_verify<V: Variant>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let secret_key = SecretKey::<V>::random(&mut rng);
+    let public_key = secret_key.public_key();
+    let signature = secret_key.sign(b"message");
+
+    assert!(bool::from(public_key.verify(b"message", &signature)));
+    assert!(!bool::from(public_key.verify(b"massage", &signature)));
+    let other = SecretKey::<V>::random(&mut rng).public_key();
+    assert!(!bool::from(other.verify(b"message", &signature)));
+
+    // Proofs of possession and signatures are not interchangeable
+    let proof = secret_key.pop_prove();
+    assert!(bool::from(public_key.pop_verify(&proof)));
+    assert!(!bool::from(other.pop_verify(&proof)));
+    let public_key_bytes = public_key.0.to_bytes();
+    assert!(!bool::from(
+        public_key.verify(public_key_bytes.as_ref(), &proof)
+    ));
+
+    // The identity is not a valid public key
+    let identity = PublicKey::<V>(V::PublicKeyGroup::identity());
+    let identity_signature = Signature::<V>(V::SignatureGroup::identity());
+    assert!(!bool::from(
+        identity.verify(b"message", &identity_signature)
+    ));
+}
+
+#[cfg(test)]
+fn test_aggregate<V: Variant>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let secret_keys: Vec<_> = (0..4).map(|_| SecretKey::<V>::random(&mut rng)).collect();
+    let public_keys: Vec<_> = secret_keys.iter().map(|sk| sk.public_key()).collect();
+
+    // Distinct messages
+    let messages: Vec<&[u8]> = vec![&b"zero"[..], b"one", b"two", b"three"];
+    let signatures: Vec<_> = secret_keys
+        .iter()
+        .zip(messages.iter())
+        .map(|(sk, message)| sk.sign(message))
+        .collect();
+    let signature = Signature::aggregate(&signatures);
+    assert!(bool::from(aggregate_verify(
+        &public_keys,
+        &messages,
+        &signature
+    )));
+    let swapped: Vec<&[u8]> = vec![&b"one"[..], b"zero", b"two", b"three"];
+    assert!(!bool::from(aggregate_verify(
+        &public_keys,
+        &swapped,
+        &signature
+    )));
+    assert!(!bool::from(aggregate_verify(
+        &public_keys[..3],
+        &messages[..3],
+        &signature
+    )));
+
+    // Repeated messages are rejected
+    let repeated: Vec<&[u8]> = vec![&b"zero"[..], b"zero"];
+    let signature =
+        Signature::aggregate(&[secret_keys[0].sign(b"zero"), secret_keys[1].sign(b"zero")]);
+    assert!(!bool::from(aggregate_verify(
+        &public_keys[..2],
+        &repeated,
+        &signature
+    )));
+
+    // Same message
+    let signatures: Vec<_> = secret_keys.iter().map(|sk| sk.sign(b"common")).collect();
+    let signature = Signature::aggregate(&signatures);
+    assert!(bool::from(fast_aggregate_verify(
+        &public_keys,
+        b"common",
+        &signature
+    )));
+    assert!(!bool::from(fast_aggregate_verify(
+        &public_keys[1..],
+        b"common",
+        &signature
+    )));
+    assert!(!bool::from(fast_aggregate_verify(
+        &public_keys,
+        b"other",
+        &signature
+    )));
+}
+
+#[cfg(test)]
+fn test_rogue_key<V: Variant>() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let victim = SecretKey::<V>::random(&mut rng).public_key();
+    let attacker = SecretKey::<V>::random(&mut rng);
+
+    // The rogue key cancels the key of the victim in the aggregate, so that
+    // the attacker alone signs for both
+    let rogue = PublicKey::<V>((attacker.public_key().0 - victim.0).to_affine());
+    let signature = attacker.sign(b"message");
+    assert!(bool::from(fast_aggregate_verify(
+        &[victim, rogue],
+        b"message",
+        &signature
+    )));
+
+    // but the attacker cannot prove the possession of the rogue key
+    assert!(!bool::from(rogue.pop_verify(&attacker.pop_prove())));
+}
+
+#[test]
+fn test_min_pk() {
+    test_sign_verify::<MinPk<Bn256>>();
+    test_aggregate::<MinPk<Bn256>>();
+    test_rogue_key::<MinPk<Bn256>>();
+}
+
+#[test]
+fn test_min_sig() {
+    test_sign_verify::<MinSig<Bn256>>();
+    test_aggregate::<MinSig<Bn256>>();
+    test_rogue_key::<MinSig<Bn256>>();
+}
+
+#[test]
+fn test_key_gen() {
+    let ikm = [0x2a; 32];
+    let secret_key = SecretKey::<MinPk<Bn256>>::key_gen(&ikm);
+    assert_eq!(
+        secret_key.public_key(),
+        SecretKey::<MinPk<Bn256>>::key_gen(&ikm).public_key()
+    );
+    assert!(
+        secret_key.public_key() != SecretKey::<MinPk<Bn256>>::key_gen(&[0x2b; 32]).public_key()
+    );
+
+    let signature = secret_key.sign(b"message");
+    assert!(bool::from(
+        secret_key.public_key().verify(b"message", &signature)
+    ));
+}
+
+#[test]
+fn test_bls12_381() {
+    test_sign_verify::<MinPk<Bls12>>();
+    test_aggregate::<MinPk<Bls12>>();
+    test_rogue_key::<MinPk<Bls12>>();
+    test_sign_verify::<MinSig<Bls12>>();
+    test_aggregate::<MinSig<Bls12>>();
+    test_rogue_key::<MinSig<Bls12>>();
+}
+
+#[cfg(test)]
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_bls12_381_vectors() {
+    // KeyGen with the first test case of EIP-2333, which follows the draft
+    let ikm = from_hex(
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553\
+         1f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+    );
+    let secret_key = SecretKey::<MinPk<Bls12>>::key_gen(&ikm);
+    assert_eq!(
+        secret_key.0,
+        Bls12Fr::from_str_vartime(
+            "6083874454709270928345386274498605044986640685124978867557563392430687146096"
+        )
+        .unwrap()
+    );
+    let public_key = secret_key.public_key();
+    assert_eq!(
+        public_key.0.to_bytes().as_ref(),
+        from_hex(
+            "a2c975348667926acf12f3eecb005044e08a7a9b7d95f30bd281b55445107367\
+             a2e5d0558be7943c8bd13f9a1a7036fb"
+        )
+    );
+    let proof = secret_key.pop_prove();
+    assert_eq!(
+        proof.0.to_bytes().as_ref(),
+        from_hex(
+            "8a4728673af25888751078e6023f1092378e219d4405efacab14a5935502e6bb\
+             45e4149d6dea817861485baa65fe1c54047ee1024c9f8d8d0215e35197bff3a9\
+             586486199dd11e3d4563b20a3b1b8eeb68c84e980d1b729d1b66280fd59e2494"
+        )
+    );
+    assert!(bool::from(public_key.pop_verify(&proof)));
+
+    // Signatures of the Ethereum consensus test vectors, which use the
+    // ciphersuite BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_
+    let mut repr = [0u8; 32];
+    repr.copy_from_slice(&from_hex(
+        "263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3",
+    ));
+    repr.reverse();
+    let secret_key = SecretKey::<MinPk<Bls12>>(Bls12Fr::from_bytes(&repr).unwrap());
+    let public_key = secret_key.public_key();
+    for (message, expected) in [
+        (
+            [0x56; 32],
+            "882730e5d03f6b42c3abc26d3372625034e1d871b65a8a6b900a56dae22da98a\
+             bbe1b68f85e49fe7652a55ec3d0591c20767677e33e5cbb1207315c41a9ac03b\
+             e39c2e7668edc043d6cb1d9fd93033caa8a1c5b0e84bedaeb6c64972503a43eb",
+        ),
+        (
+            [0x00; 32],
+            "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6\
+             076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24\
+             802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55",
+        ),
+    ] {
+        let signature = secret_key.sign(&message);
+        assert_eq!(signature.0.to_bytes().as_ref(), from_hex(expected));
+        assert!(bool::from(public_key.verify(&message, &signature)));
+    }
+}
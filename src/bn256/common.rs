@@ -99,6 +99,26 @@ macro_rules! common_field {
 
                 $field([d0, d1, d2, d3])
             }
+
+            /// Computes the multiplicative inverse of this element with the
+            /// constant-time safegcd algorithm of Bernstein and Yang, failing
+            /// if the element is zero. This is what `Field::invert` uses.
+            pub fn invert_safegcd(&self) -> CtOption<$field> {
+                // The inverse of aR is a^-1 R^-1, which a Montgomery multiplication
+                // by R^3 brings back into Montgomery form
+                let tmp = $field(safegcd_invert(&self.0, &MODULUS.0, INV)) * R3;
+
+                CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+            }
+
+            /// Computes the multiplicative inverse of this element as
+            /// $a^{p - 2}$ with a constant-time exponentiation, failing if the
+            /// element is zero.
+            pub fn invert_pow(&self) -> CtOption<$field> {
+                let tmp = self.pow(&[MODULUS.0[0] - 2, MODULUS.0[1], MODULUS.0[2], MODULUS.0[3]]);
+
+                CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+            }
         }
 
         impl Group for $field {
@@ -514,6 +534,66 @@ macro_rules! common_field {
             }
         }
 
+        impl Legendre for $field {
+            /// Computes the Legendre symbol with a constant-time binary GCD on
+            /// the canonical representation of this element and the modulus.
+            ///
+            /// Each iteration makes `a` even, possibly after swapping it with
+            /// `b` and subtracting, then halves it; the sign is tracked with
+            /// the quadratic reciprocity law and the second supplementary law
+            /// $(2 / b) = (-1)^{(b^2 - 1) / 8}$. As the bit lengths of `a` and
+            /// `b` add up to at most 512 and each iteration decreases their sum,
+            /// `a` is zero and `b` is one after 512 iterations.
+            fn jacobi(&self) -> i64 {
+                #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+                let tmp = $field::montgomery_reduce(&[
+                    self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0,
+                ]);
+
+                #[cfg(any(not(feature = "asm"), not(target_arch = "x86_64")))]
+                let tmp = $field::montgomery_reduce(
+                    self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0,
+                );
+
+                let mut a = tmp.0;
+                let mut b = $modulus.0;
+                let mut ls = 0u64;
+                for _ in 0..512 {
+                    // Swap a and b if a is odd and a < b
+                    let (_, borrow) = sbb(a[0], b[0], 0);
+                    let (_, borrow) = sbb(a[1], b[1], borrow);
+                    let (_, borrow) = sbb(a[2], b[2], borrow);
+                    let (_, borrow) = sbb(a[3], b[3], borrow);
+                    let a_odd = (a[0] & 1).wrapping_neg();
+                    let swap = a_odd & borrow;
+                    for (a, b) in a.iter_mut().zip(b.iter_mut()) {
+                        let t = swap & (*a ^ *b);
+                        *a ^= t;
+                        *b ^= t;
+                    }
+                    // (a / b) = -(b / a) if a = b = 3 mod 4
+                    ls ^= swap & ((a[0] & b[0]) >> 1);
+
+                    // a = (a - b) / 2 if a is odd, a / 2 otherwise
+                    let (d0, borrow) = sbb(a[0], b[0] & a_odd, 0);
+                    let (d1, borrow) = sbb(a[1], b[1] & a_odd, borrow);
+                    let (d2, borrow) = sbb(a[2], b[2] & a_odd, borrow);
+                    let (d3, _) = sbb(a[3], b[3] & a_odd, borrow);
+                    a = [
+                        (d0 >> 1) | (d1 << 63),
+                        (d1 >> 1) | (d2 << 63),
+                        (d2 >> 1) | (d3 << 63),
+                        d3 >> 1,
+                    ];
+                    // (2 / b) = -1 if b = 3, 5 mod 8
+                    ls ^= b[0].wrapping_add(2) >> 2;
+                }
+
+                let res = 1 - 2 * ((ls & 1) as i64);
+                i64::conditional_select(&res, &0, self.ct_is_zero())
+            }
+        }
+
         #[test]
         fn test_inv() {
             // Compute -(r^{-1} mod 2^64) mod 2^64 by exponentiating
@@ -528,6 +608,28 @@ macro_rules! common_field {
 
             assert_eq!(inv, INV);
         }
+
+        #[test]
+        fn test_safegcd_invert() {
+            let mut rng = XorShiftRng::from_seed([
+                0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+                0xbc, 0xe5,
+            ]);
+
+            // Cross-check against Fermat's little theorem, a^(p - 2)
+            assert!(bool::from($field::zero().invert_safegcd().is_none()));
+            assert!(bool::from($field::zero().invert_pow().is_none()));
+            assert_eq!($field::one().invert_safegcd().unwrap(), $field::one());
+            assert_eq!((-$field::one()).invert_safegcd().unwrap(), -$field::one());
+
+            for _ in 0..1000 {
+                let a = $field::random(&mut rng);
+                let inv = a.invert_safegcd().unwrap();
+                assert_eq!(inv, a.invert_pow().unwrap());
+                assert_eq!(inv, a.invert().unwrap());
+                assert_eq!(inv * a, $field::one());
+            }
+        }
     };
 }
 
@@ -1,18 +1,20 @@
 use crate::arithmetic::{
-    Engine, MillerLoopResult, MultiMillerLoop, MultiMillerLoopOnProvePairing, PairingCurveAffine,
+    Engine, FieldExt, MillerLoopResult, MillerLoopStrategies, MillerLoopStrategy, MultiMillerLoop,
+    MultiMillerLoopOnProvePairing, PairingCurveAffine,
 };
 use crate::bn256::fq::*;
 use crate::bn256::fq12::*;
 use crate::bn256::fq2::*;
-use crate::bn256::fq6::FROBENIUS_COEFF_FQ6_C1;
+use crate::bn256::fq6::{Fq6, FROBENIUS_COEFF_FQ6_C1};
 use crate::bn256::fr::*;
 use crate::bn256::g::*;
 use core::borrow::Borrow;
+use core::convert::TryInto;
 use core::iter::Sum;
 use core::ops::{Add, Mul, MulAssign, Neg, Sub};
 use ff::{Field, PrimeField};
 use group::cofactor::CofactorCurveAffine;
-use group::Group;
+use group::{Group, GroupEncoding};
 use rand_core::RngCore;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -58,9 +60,169 @@ impl PairingCurveAffine for G2Affine {
     }
 }
 
+/// An element of the order-r subgroup of the multiplicative group of
+/// $\mathbb{F}_{q^{12}}$, written multiplicatively in `Fq12` but exposed
+/// through the additive `Group` interface.
+///
+/// The wrapped `Fq12` is public for use inside the pairing computations and
+/// is not checked: building a `Gt` directly from an arbitrary `Fq12` may give
+/// an element outside the subgroup, for which the group operations are
+/// meaningless. Values from untrusted sources must go through
+/// `Gt::from_fq12` or the `GroupEncoding` and `Gt::decompress` decoders.
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Gt(pub Fq12);
 
+// e(G1::generator(), G2::generator())
+pub const GT_GENERATOR: Gt = Gt(Fq12 {
+    c0: Fq6 {
+        c0: Fq2 {
+            c0: Fq([
+                0xc556f62b2a98671d,
+                0x23a59ac167bcf363,
+                0x5ef208445f5f6f37,
+                0x12adf27ccb29382a,
+            ]),
+            c1: Fq([
+                0x2e02a64acbd60549,
+                0xd618018ea58e4add,
+                0x14d585f1a45ba647,
+                0x1832226987c434fc,
+            ]),
+        },
+        c1: Fq2 {
+            c0: Fq([
+                0x2306e4312363b991,
+                0x465f6072d4023bf4,
+                0xa2ff062a4a77e736,
+                0x076ea6f18435864a,
+            ]),
+            c1: Fq([
+                0x172d1f257a4d598e,
+                0xddf5bc7b7ffb5ac0,
+                0xae0b22c0bbb0f602,
+                0x1b158f3c2fae9b18,
+            ]),
+        },
+        c2: Fq2 {
+            c0: Fq([
+                0x5cf9cc917da86724,
+                0xc799dc487a0b2753,
+                0x0df2027bf1de17a7,
+                0x197cda6cc3e20636,
+            ]),
+            c1: Fq([
+                0xf16c96d081754cdb,
+                0xce0394312bceeb55,
+                0x644e4dcf1f01ff0a,
+                0x0cbea85ee0b236cc,
+            ]),
+        },
+    },
+    c1: Fq6 {
+        c0: Fq2 {
+            c0: Fq([
+                0x1bb0ce0def1b82a1,
+                0x4c4c9fe1cadefa95,
+                0x746d9990cb12b27e,
+                0x13495c08e5d415c5,
+            ]),
+            c1: Fq([
+                0x9458abcb56d24998,
+                0xb17540bd2a9e5adb,
+                0x9a9983c82e401a9f,
+                0x1614817a84c16291,
+            ]),
+        },
+        c1: Fq2 {
+            c0: Fq([
+                0x8975b68a2bab1f9c,
+                0x2fdd826b796e0f35,
+                0x6a90a35fa03dfaa5,
+                0x1ffef4581607fc37,
+            ]),
+            c1: Fq([
+                0x7002907c28ebfe11,
+                0x7b0591d3d080da67,
+                0xde7e5aa2181f138e,
+                0x210e437dfc43d951,
+            ]),
+        },
+        c2: Fq2 {
+            c0: Fq([
+                0x988ae2485b36cf53,
+                0x5091cc0581334e54,
+                0xda7903229312ca0f,
+                0x2a2341538eaee95c,
+            ]),
+            c1: Fq([
+                0xd34bab373157aa84,
+                0x3511ed44fd0d8598,
+                0x67e42a0bc2ced972,
+                0x2b8f1d5dfd20c55b,
+            ]),
+        },
+    },
+});
+
+#[derive(Copy, Clone)]
+pub struct GtUncompressed([u8; Fq12::size()]);
+
+impl std::fmt::Debug for GtUncompressed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0[..].fmt(f)
+    }
+}
+
+impl Default for GtUncompressed {
+    fn default() -> Self {
+        GtUncompressed([0; Fq12::size()])
+    }
+}
+
+impl AsRef<[u8]> for GtUncompressed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for GtUncompressed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+// Flags in the most significant bits of a compressed Gt element
+const GT_IDENTITY_FLAG: u8 = 1 << 7;
+const GT_G0_IS_ZERO_FLAG: u8 = 1 << 6;
+
+/// A `Gt` element compressed to a third of its size, see `Gt::compress`.
+#[derive(Copy, Clone)]
+pub struct GtCompressed([u8; 2 * Fq2::size()]);
+
+impl std::fmt::Debug for GtCompressed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0[..].fmt(f)
+    }
+}
+
+impl Default for GtCompressed {
+    fn default() -> Self {
+        GtCompressed([0; 2 * Fq2::size()])
+    }
+}
+
+impl AsRef<[u8]> for GtCompressed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for GtCompressed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
 impl std::fmt::Display for Gt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -97,6 +259,292 @@ impl Gt {
     pub fn double(&self) -> Gt {
         Gt(self.0.square())
     }
+
+    /// Returns `f` as a `Gt` element if it lies in the order-r subgroup.
+    pub fn from_fq12(f: &Fq12) -> CtOption<Gt> {
+        let f = Gt(*f);
+        CtOption::new(f, f.is_in_subgroup())
+    }
+
+    /// Checks whether this element lies in the order-r subgroup.
+    ///
+    /// The element is first checked to be in the cyclotomic subgroup of order
+    /// $\Phi_{12}(q) = q^4 - q^2 + 1$. Since $q \equiv 6x^2 \pmod r$ and
+    /// $\gcd(q - 6x^2, \Phi_{12}(q)) = r$, such an element is in the order-r
+    /// subgroup if and only if $f^q = f^{6x^2}$, which costs a Frobenius map
+    /// and two exponentiations by $x$ instead of one by $r$.
+    pub fn is_in_subgroup(&self) -> Choice {
+        let f = self.0;
+
+        // f^(q^6 + 1) = 1, which also rules out zero
+        let mut f_conj = f;
+        f_conj.conjugate();
+        let is_unitary = (f * f_conj).ct_eq(&Fq12::one());
+
+        // f^(q^4 + 1) = f^(q^2)
+        let mut fp2 = f;
+        fp2.frobenius_map(2);
+        let mut fp4 = fp2;
+        fp4.frobenius_map(2);
+        let is_cyclotomic = (fp4 * f).ct_eq(&fp2);
+
+        // f^q = f^(6x^2)
+        let mut fp = f;
+        fp.frobenius_map(1);
+        let mut fx2 = f;
+        exp_by_x(&mut fx2);
+        exp_by_x(&mut fx2);
+        let mut fx2_2 = fx2;
+        fx2_2.cyclotomic_square();
+        let mut fx2_4 = fx2_2;
+        fx2_4.cyclotomic_square();
+        let is_r_torsion = fp.ct_eq(&(fx2_2 * fx2_4));
+
+        is_unitary & is_cyclotomic & is_r_torsion
+    }
+
+    /// Raises this element to the power `by` in constant time, which is the
+    /// scalar multiplication of the additively written group.
+    ///
+    /// On the order-r subgroup the Frobenius map acts as exponentiation by
+    /// $\lambda = 6x^2 \bmod r$, so `by` is split as
+    /// $k_0 + k_1 \lambda + k_2 \lambda^2 + k_3 \lambda^3$ with 65-bit
+    /// $k_i$ and the four exponentiations of $f^{q^i}$ are done jointly with
+    /// cyclotomic squarings, see Galbraith and Scott "Exponentiation in
+    /// pairing-friendly groups using homomorphisms"
+    /// (https://eprint.iacr.org/2008/117.pdf).
+    pub fn pow(&self, by: &Fr) -> Gt {
+        let mut bases = [self.0; 4];
+        for i in 1..4 {
+            bases[i] = bases[i - 1];
+            bases[i].frobenius_map(1);
+        }
+
+        let mut exponents = [0u128; 4];
+        for ((base, exponent), k) in bases
+            .iter_mut()
+            .zip(exponents.iter_mut())
+            .zip(gt_glv_decompose(by).iter())
+        {
+            // |k| and its sign, without branching
+            let mask = (k >> 127) as u128;
+            *exponent = ((*k as u128) ^ mask).wrapping_sub(mask);
+            let mut base_conj = *base;
+            base_conj.conjugate();
+            base.conditional_assign(&base_conj, Choice::from((mask & 1) as u8));
+        }
+
+        // table[i] is the product of the bases selected by the bits of i
+        let mut table = [Fq12::one(); 16];
+        for i in 1..table.len() {
+            table[i] = table[i & (i - 1)] * bases[i.trailing_zeros() as usize];
+        }
+
+        let mut acc = Fq12::one();
+        for bit in (0..GT_GLV_BITS).rev() {
+            acc.cyclotomic_square();
+            let index = exponents.iter().enumerate().fold(0, |index, (i, e)| {
+                index | ((((e >> bit) & 1) as usize) << i)
+            });
+            let mut t = Fq12::one();
+            for (i, entry) in table.iter().enumerate() {
+                t.conditional_assign(entry, (i as u64).ct_eq(&(index as u64)));
+            }
+            acc *= t;
+        }
+
+        Gt(acc)
+    }
+
+    /// Returns the square root of this element in the multiplicative group
+    /// $\mathbb{F}_{q^{12}}^*$ that lies in Gt, that is its half in the
+    /// additively written group, if this element is in Gt.
+    pub fn sqrt(&self) -> CtOption<Gt> {
+        // Gt has odd order r, so each element has a unique square root in
+        // the group, self^((r + 1) / 2). The other root in Fq12 is its
+        // negation, which is not in Gt.
+        let res = self.pow(&Fr::TWO_INV);
+        CtOption::new(res, res.double().ct_eq(self))
+    }
+
+    /// Compresses this element into 128 bytes.
+    ///
+    /// An element $f = c_0 + c_1 w \neq 1$ of the torus $T_2(\mathbb{F}_{q^6})$
+    /// is represented by $g = (1 + c_0) / c_1$, recovered as
+    /// $f = (g + w) / (g - w)$. Writing $g = g_0 + g_1 v + g_2 v^2$, elements of
+    /// $T_6(\mathbb{F}_{q^2})$ further satisfy $3 g_0 g_1 = 1 + 3 \xi g_2^2$,
+    /// so only $(g_0, g_2)$ is stored. The two most significant bits of the
+    /// last byte flag the identity and the case $g_0 = 0$, in which
+    /// $(g_1, g_2)$ is stored instead.
+    pub fn compress(&self) -> GtCompressed {
+        let is_identity = self.0.c1.is_zero();
+        let g = (self.0.c0 + Fq6::one()) * self.0.c1.invert().unwrap_or(Fq6::zero());
+        let g0_is_zero = g.c0.is_zero() & !is_identity;
+
+        let mut res = [0u8; 2 * Fq2::size()];
+        res[0..64].copy_from_slice(&Fq2::conditional_select(&g.c0, &g.c1, g0_is_zero).to_bytes());
+        res[64..128].copy_from_slice(&g.c2.to_bytes());
+        res[127] |= u8::conditional_select(&0, &GT_IDENTITY_FLAG, is_identity);
+        res[127] |= u8::conditional_select(&0, &GT_G0_IS_ZERO_FLAG, g0_is_zero);
+
+        GtCompressed(res)
+    }
+
+    /// Attempts to decompress an element produced by `Gt::compress`, failing
+    /// if the encoding is not canonical or the element is not in the
+    /// r-torsion subgroup.
+    pub fn decompress(bytes: &GtCompressed) -> CtOption<Gt> {
+        let mut bytes = bytes.0;
+        let is_identity = Choice::from((bytes[127] & GT_IDENTITY_FLAG) >> 7);
+        let g0_is_zero = Choice::from((bytes[127] & GT_G0_IS_ZERO_FLAG) >> 6);
+        bytes[127] &= !(GT_IDENTITY_FLAG | GT_G0_IS_ZERO_FLAG);
+
+        let a = Fq2::from_bytes(bytes[0..64].try_into().unwrap());
+        let g2 = Fq2::from_bytes(bytes[64..128].try_into().unwrap());
+        let is_canonical = a.is_some() & g2.is_some();
+        let a = a.unwrap_or(Fq2::zero());
+        let g2 = g2.unwrap_or(Fq2::zero());
+
+        // 1 + 3 xi g2^2
+        let mut t = g2.square();
+        t.mul_by_nonresidue();
+        let t = t + t + t + Fq2::one();
+
+        let g = Fq6 {
+            c0: Fq2::conditional_select(&a, &Fq2::zero(), g0_is_zero),
+            c1: Fq2::conditional_select(
+                &(t * (a + a + a).invert().unwrap_or(Fq2::zero())),
+                &a,
+                g0_is_zero,
+            ),
+            c2: g2,
+        };
+        let is_valid = Choice::conditional_select(
+            &Choice::conditional_select(&!a.is_zero(), &t.is_zero(), g0_is_zero),
+            &(a.is_zero() & g2.is_zero() & !g0_is_zero),
+            is_identity,
+        );
+
+        // (g + w) / (g - w) = ((g^2 + v) + 2 g w) / (g^2 - v), where g^2 - v
+        // is nonzero as v is not a square in Fq6
+        let v = Fq6 {
+            c0: Fq2::zero(),
+            c1: Fq2::one(),
+            c2: Fq2::zero(),
+        };
+        let g_square = g.square();
+        let d = (g_square - v).invert().unwrap_or(Fq6::zero());
+        let f = Fq12 {
+            c0: (g_square + v) * d,
+            c1: (g + g) * d,
+        };
+        let f = Fq12::conditional_select(&f, &Fq12::one(), is_identity);
+
+        CtOption::new(Gt(f), is_canonical & is_valid & Gt(f).is_in_subgroup())
+    }
+}
+
+impl GroupEncoding for Gt {
+    type Repr = GtUncompressed;
+
+    fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
+        Fq12::from_bytes(&bytes.0).and_then(|f| Gt::from_fq12(&f))
+    }
+
+    fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
+        Fq12::from_bytes(&bytes.0).map(Gt)
+    }
+
+    fn to_bytes(&self) -> Self::Repr {
+        GtUncompressed(self.0.to_bytes())
+    }
+}
+
+// Bit length bound of the scalars returned by `gt_glv_decompose`
+const GT_GLV_BITS: usize = 65;
+
+// Basis of the lattice of (a_0, a_1, a_2, a_3) with
+// a_0 + a_1 lambda + a_2 lambda^2 + a_3 lambda^3 = 0 mod r, lambda = 6x^2
+const GT_GLV_BASIS: [[i128; 4]; 4] = {
+    let x = BN_X as i128;
+    [
+        [2 * x + 1, 0, 2 * x, 1],
+        [2 * x, x + 1, -x, x],
+        [x + 1, x, x, -2 * x],
+        [2 * x + 1, -x, -(x + 1), -x],
+    ]
+};
+
+// round(2^384 * alpha_j), where (1, 0, 0, 0) = sum_j alpha_j * GT_GLV_BASIS[j]
+const GT_GLV_ALPHA: [[u64; 5]; 4] = [
+    [
+        0xc638c5d5d685c43f,
+        0x353ccca0e558c73b,
+        0x2dff291532e42728,
+        0x55b4ca7ba3e5577f,
+        0x9e80318ab0d92b95,
+    ],
+    [
+        0x500bb9ebe34b87b6,
+        0x7d1fff2e5ce18e26,
+        0x46f4bda995d51bb1,
+        0x08e5da66fc7184ae,
+        0x9e80318ab0d92b93,
+    ],
+    [
+        0x8fa7d32d2fafba64,
+        0x6eb9c714773a6ef2,
+        0xd91d232ec7e0b3d7,
+        0x0000000000000002,
+        0x0000000000000000,
+    ],
+    [
+        0xd69f2f027ee767b0,
+        0x23038c29bb8bb4ff,
+        0xc170977dcef3cd3f,
+        0x55b4ca7ba3e5577d,
+        0x9e80318ab0d92b95,
+    ],
+];
+
+// Splits k into (k_0, k_1, k_2, k_3) with |k_i| < 2^65 and
+// k = k_0 + k_1 lambda + k_2 lambda^2 + k_3 lambda^3 mod r, by rounding
+// (k, 0, 0, 0) to a nearby lattice vector. The k_i are small, so they can be
+// computed modulo 2^128.
+fn gt_glv_decompose(k: &Fr) -> [i128; 4] {
+    let repr = k.to_repr();
+    let mut k = [0u64; 4];
+    for (limb, bytes) in k.iter_mut().zip(repr.as_ref().chunks(8)) {
+        *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let mut res = [0u128; 4];
+    res[0] = (k[0] as u128) | ((k[1] as u128) << 64);
+    for (alpha, basis) in GT_GLV_ALPHA.iter().zip(GT_GLV_BASIS.iter()) {
+        // c = floor(k * alpha / 2^384) mod 2^128
+        let mut product = [0u64; 9];
+        for (i, a) in k.iter().enumerate() {
+            let mut carry = 0;
+            for (j, b) in alpha.iter().enumerate() {
+                let t = (*a as u128) * (*b as u128) + (product[i + j] as u128) + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + alpha.len()] = carry as u64;
+        }
+        let c = (product[6] as u128) | ((product[7] as u128) << 64);
+
+        for (r, b) in res.iter_mut().zip(basis.iter()) {
+            *r = r.wrapping_sub(c.wrapping_mul(*b as u128));
+        }
+    }
+
+    [
+        res[0] as i128,
+        res[1] as i128,
+        res[2] as i128,
+        res[3] as i128,
+    ]
 }
 
 impl<'a> Neg for &'a Gt {
@@ -123,9 +571,11 @@ impl Neg for Gt {
 impl<'a, 'b> Add<&'b Gt> for &'a Gt {
     type Output = Gt;
 
+    // The group law, written additively
     #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn add(self, rhs: &'b Gt) -> Gt {
-        Gt(self.0 + rhs.0)
+        Gt(self.0 * rhs.0)
     }
 }
 
@@ -142,20 +592,7 @@ impl<'a, 'b> Mul<&'b Fr> for &'a Gt {
     type Output = Gt;
 
     fn mul(self, other: &'b Fr) -> Self::Output {
-        let mut acc = Gt::identity();
-
-        for bit in other
-            .to_repr()
-            .iter()
-            .rev()
-            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-            .skip(1)
-        {
-            acc = acc.double();
-            acc = Gt::conditional_select(&acc, &(acc + self), bit);
-        }
-
-        acc
+        self.pow(other)
     }
 }
 
@@ -186,8 +623,15 @@ where
 impl Group for Gt {
     type Scalar = Fr;
 
-    fn random(_: impl RngCore) -> Self {
-        unimplemented!();
+    fn random(mut rng: impl RngCore) -> Self {
+        // The final exponentiation maps Fq12^* onto the r-torsion subgroup
+        // with fibers of equal size, so a uniform input gives a uniform output
+        loop {
+            let f = Fq12::random(&mut rng);
+            if !bool::from(f.is_zero()) {
+                return Gt(f).final_exponentiation();
+            }
+        }
     }
 
     fn identity() -> Self {
@@ -195,7 +639,7 @@ impl Group for Gt {
     }
 
     fn generator() -> Self {
-        unimplemented!();
+        GT_GENERATOR
     }
 
     fn is_identity(&self) -> Choice {
@@ -208,33 +652,6 @@ impl Group for Gt {
     }
 }
 
-impl Field for Gt {
-    fn random(mut rng: impl RngCore) -> Self {
-        Self(Fq12::random(&mut rng))
-    }
-    fn zero() -> Self {
-        Self(Fq12::zero())
-    }
-    fn one() -> Self {
-        Self(Fq12::one())
-    }
-    fn is_zero(&self) -> Choice {
-        self.0.is_zero()
-    }
-    fn square(&self) -> Self {
-        Self(self.0.square())
-    }
-    fn double(&self) -> Self {
-        Self(self.0.double())
-    }
-    fn sqrt(&self) -> CtOption<Self> {
-        unimplemented!();
-    }
-    fn invert(&self) -> CtOption<Self> {
-        self.0.invert().map(|t| Gt(t))
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct G2Prepared {
     pub(crate) coeffs: Vec<(Fq2, Fq2, Fq2)>,
@@ -628,105 +1045,152 @@ impl From<G2Affine> for G2OnProvePrepared {
     }
 }
 
+// Squares f, which is in the cyclotomic subgroup, n times. Long runs are
+// squared in Karabina's compressed form and decompressed once at the end;
+// below 8 squarings the inversion in the decompression costs more than the
+// compressed squarings save.
+fn cyclotomic_square_n(f: &mut Fq12, n: usize) {
+    if n < 8 {
+        for _ in 0..n {
+            f.cyclotomic_square();
+        }
+    } else {
+        for _ in 0..n {
+            f.compressed_cyclotomic_square();
+        }
+        f.decompress();
+    }
+}
+
+// Raises f, which is in the cyclotomic subgroup, to BN_X with the addition
+// chain below (62 squarings and 17 multiplications), whose runs of 6 to 10
+// squarings between the multiplications go through `cyclotomic_square_n`.
+//
+//   _10     = 2 * 1
+//   _100    = 2 * _10
+//   _1000   = 2 * _100
+//   _10000  = 2 * _1000
+//   _10001  = 1 + _10000
+//   _10011  = _10 + _10001
+//   _10100  = 1 + _10011
+//   _11001  = _1000 + _10001
+//   _100010 = 2 * _10001
+//   _100111 = _10011 + _10100
+//   _101001 = _10 + _100111
+//   i27     = (_100010 << 6 + _100 + _11001) << 7 + _11001
+//   i44     = (i27 << 8 + _101001 + _10) << 6 + _10001
+//   i70     = ((i44 << 8 + _101001) << 6 + _101001) << 10
+//   BN_X    = (_100111 + i70) << 6 + _101001 + _1000
+fn exp_by_x(f: &mut Fq12) {
+    let mut x10 = *f;
+    x10.cyclotomic_square();
+    let mut x100 = x10;
+    x100.cyclotomic_square();
+    let mut x1000 = x100;
+    x1000.cyclotomic_square();
+    let mut x10000 = x1000;
+    x10000.cyclotomic_square();
+    let x10001 = *f * x10000;
+    let x10011 = x10 * x10001;
+    let x10100 = *f * x10011;
+    let x11001 = x1000 * x10001;
+    let mut x100010 = x10001;
+    x100010.cyclotomic_square();
+    let x100111 = x10011 * x10100;
+    let x101001 = x10 * x100111;
+
+    let mut res = x100010;
+    cyclotomic_square_n(&mut res, 6);
+    res *= x100 * x11001;
+    cyclotomic_square_n(&mut res, 7);
+    res *= x11001;
+    cyclotomic_square_n(&mut res, 8);
+    res *= x101001 * x10;
+    cyclotomic_square_n(&mut res, 6);
+    res *= x10001;
+    cyclotomic_square_n(&mut res, 8);
+    res *= x101001;
+    cyclotomic_square_n(&mut res, 6);
+    res *= x101001;
+    cyclotomic_square_n(&mut res, 10);
+    res *= x100111;
+    cyclotomic_square_n(&mut res, 6);
+    *f = res * x101001 * x1000;
+}
+
 impl MillerLoopResult for Gt {
     type Gt = Self;
     fn final_exponentiation(&self) -> Gt {
-        fn exp_by_x(f: &mut Fq12) {
-            let x = BN_X;
-            let mut res = Fq12::one();
-            for i in (0..64).rev() {
-                res.cyclotomic_square();
-                if ((x >> i) & 1) == 1 {
-                    res.mul_assign(f);
-                }
-            }
-            *f = res;
-        }
-
         let r = self.0;
         let mut f1 = self.0;
         f1.conjugate();
 
         Gt(r.invert()
             .map(|mut f2| {
+                // Easy part: f^((p^6 - 1)(p^2 + 1))
                 let mut r = f1;
                 r.mul_assign(&f2);
                 f2 = r;
                 r.frobenius_map(2);
                 r.mul_assign(&f2);
 
-                let mut fp = r;
-                fp.frobenius_map(1);
-
-                let mut fp2 = r;
-                fp2.frobenius_map(2);
-                let mut fp3 = fp2;
-                fp3.frobenius_map(1);
-
-                let mut fu = r;
-                exp_by_x(&mut fu);
-
-                let mut fu2 = fu;
-                exp_by_x(&mut fu2);
-
-                let mut fu3 = fu2;
-                exp_by_x(&mut fu3);
-
-                let mut y3 = fu;
-                y3.frobenius_map(1);
-
-                let mut fu2p = fu2;
-                fu2p.frobenius_map(1);
-
-                let mut fu3p = fu3;
-                fu3p.frobenius_map(1);
-
-                let mut y2 = fu2;
-                y2.frobenius_map(2);
-
-                let mut y0 = fp;
-                y0.mul_assign(&fp2);
-                y0.mul_assign(&fp3);
-
-                let mut y1 = r;
-                y1.conjugate();
-
-                let mut y5 = fu2;
-                y5.conjugate();
-
-                y3.conjugate();
-
-                let mut y4 = fu;
-                y4.mul_assign(&fu2p);
-                y4.conjugate();
-
-                let mut y6 = fu3;
-                y6.mul_assign(&fu3p);
-                y6.conjugate();
-
-                y6.cyclotomic_square();
-                y6.mul_assign(&y4);
-                y6.mul_assign(&y5);
-
-                let mut t1 = y3;
-                t1.mul_assign(&y5);
-                t1.mul_assign(&y6);
-
-                y6.mul_assign(&y2);
-
-                t1.cyclotomic_square();
-                t1.mul_assign(&y6);
-                t1.cyclotomic_square();
-
-                let mut t0 = t1;
-                t0.mul_assign(&y1);
-
-                t1.mul_assign(&y0);
-
-                t0.cyclotomic_square();
-                t0.mul_assign(&t1);
-
-                t0
+                // Hard part: f^((p^4 - p^2 + 1) / r). As in Fuentes-Castaneda
+                // et al. "Faster hashing to G2" and Duquesne and Ghammam
+                // "Memory-saving computation of the pairing final
+                // exponentiation on BN curves" (https://eprint.iacr.org/2015/192.pdf),
+                // the exponent is written in base p with coefficients that
+                // are polynomials in x,
+                //   l0 = -2 - 6x (6x^2 + 5x + 3)
+                //   l1 =  1 - 6x (6x^2 + 3x + 2)
+                //   l2 =  1 + 6x^2
+                //   l3 =  1
+                // but unlike Fuentes-Castaneda et al. the exponent itself is
+                // used rather than a multiple of it, so that the pairing is
+                // unchanged. With a = f^(6x^2 + 1) and b = a f^(3x + 1), we
+                // have f^l2 = a, f^l1 = f / (b^x)^6 and f^l0 = f^l1 f / b^2,
+                // which costs three exponentiations by x, 12 multiplications
+                // and 6 squarings.
+                let mut fx = r;
+                exp_by_x(&mut fx);
+                let mut fx2 = fx;
+                exp_by_x(&mut fx2);
+
+                // a = f^(6x^2 + 1)
+                let mut fx2_2 = fx2;
+                fx2_2.cyclotomic_square();
+                let mut a = fx2_2 * fx2;
+                a.cyclotomic_square();
+                a *= r;
+
+                // b = f^(6x^2 + 3x + 2)
+                let mut b = fx;
+                b.cyclotomic_square();
+                b *= fx * a * r;
+
+                // f^l1 = f / (b^x)^6
+                let mut bx = b;
+                exp_by_x(&mut bx);
+                let mut bx_3 = bx;
+                bx_3.cyclotomic_square();
+                bx_3 *= bx;
+                let mut l1 = bx_3;
+                l1.cyclotomic_square();
+                l1.conjugate();
+                l1 *= r;
+
+                // f^l0 = f^l1 f / b^2
+                let mut l0 = b;
+                l0.cyclotomic_square();
+                l0.conjugate();
+                l0 *= l1 * r;
+
+                // f^l0 (f^l1)^p a^(p^2) f^(p^3)
+                l1.frobenius_map(1);
+                a.frobenius_map(2);
+                let mut fp3 = r;
+                fp3.frobenius_map(3);
+
+                l0 * l1 * a * fp3
             })
             .unwrap())
     }
@@ -795,6 +1259,24 @@ pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
     Gt(f)
 }
 
+/// Computes the same value as `multi_miller_loop`, splitting the terms into
+/// one chunk per thread and multiplying the partial results together.
+#[cfg(feature = "multicore")]
+pub fn multi_miller_loop_parallel(terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
+    use rayon::prelude::*;
+
+    if terms.is_empty() {
+        return Gt(Fq12::one());
+    }
+
+    let chunk_size =
+        (terms.len() + rayon::current_num_threads() - 1) / rayon::current_num_threads();
+    terms
+        .par_chunks(chunk_size)
+        .map(multi_miller_loop)
+        .reduce(|| Gt(Fq12::one()), |a, b| a * b)
+}
+
 // support on prove pairing verify from affine coordinates coeffs(slope,bias)
 // verify first coeffs by init_q and calculate next q to verify next coeffs iteratively.
 pub fn multi_miller_loop_on_prove_pairing(
@@ -802,12 +1284,29 @@ pub fn multi_miller_loop_on_prove_pairing(
     wi: &Gt,
     terms: &[(&G1Affine, &G2OnProvePrepared)],
 ) -> Gt {
+    let f = miller_loop_on_prove_pairing_c_wi(c_gt, wi, terms).unwrap();
+    assert_eq!(f, Fq12::one());
+    Gt(f)
+}
+
+/// Computes the affine Miller loop of the terms combined with `c` and `wi`,
+/// which is one iff they prove that the pairing product is the identity.
+/// Returns none if `c` is not invertible or if the line coefficients of the
+/// terms do not match their points.
+fn miller_loop_on_prove_pairing_c_wi(
+    c_gt: &Gt,
+    wi: &Gt,
+    terms: &[(&G1Affine, &G2OnProvePrepared)],
+) -> CtOption<Fq12> {
     let c = c_gt.0;
-    let mut pairs = vec![];
-    let mut init_q = vec![];
-    for &(_, q) in terms.iter() {
-        init_q.push(q.init_q);
-    }
+    // Terms with an identity on either side contribute one to the product
+    // and are skipped, so every list below is built from the remaining ones
+    let terms: Vec<_> = terms
+        .iter()
+        .filter(|(p, q)| !bool::from(p.is_identity()) && !q.is_zero())
+        .collect();
+
+    let init_q: Vec<_> = terms.iter().map(|(_, q)| q.init_q).collect();
     let mut init_frobenius_q = vec![];
     for q in init_q.iter() {
         let mut q1 = q.clone();
@@ -823,22 +1322,16 @@ pub fn multi_miller_loop_on_prove_pairing(
         init_frobenius_q.push((q1, minusq2))
     }
 
-    for &(p, q) in terms {
-        if !bool::from(p.is_identity()) && !bool::from(q.is_zero()) {
-            pairs.push((p, q.coeffs.iter()));
-        }
-    }
+    let mut pairs: Vec<_> = terms.iter().map(|(p, q)| (*p, q.coeffs.iter())).collect();
 
-    fn double_verify((alpha, bias): &(Fq2, Fq2), r: &mut G2Affine) {
+    // Returns whether the coefficients are the tangent line at r
+    fn double_verify((alpha, bias): &(Fq2, Fq2), r: &mut G2Affine) -> Choice {
         // y - alpha*x - bias =0
-        assert_eq!(Fq2::zero(), r.y - alpha.mul(&r.x) - bias);
+        let on_line = (r.y - alpha.mul(&r.x) - bias).is_zero();
         // 3x^2 = alpha * 2y
         let fq2_two = Fq2::one().double();
         let fq2_three = fq2_two + Fq2::one();
-        assert_eq!(
-            Fq2::zero(),
-            r.y.mul(&fq2_two).mul(alpha) - r.x.square().mul(&fq2_three)
-        );
+        let is_tangent = (r.y.mul(&fq2_two).mul(alpha) - r.x.square().mul(&fq2_three)).is_zero();
         //x3 = alpha^2-2x
         let x3 = alpha.square() - r.x.mul(&fq2_two);
         //y3 = -alpha*x3 - bias
@@ -846,11 +1339,13 @@ pub fn multi_miller_loop_on_prove_pairing(
 
         r.x = x3;
         r.y = y3;
+        on_line & is_tangent
     }
-    fn addition_verify((alpha, bias): &(Fq2, Fq2), r: &mut G2Affine, p: &G2Affine) {
+    // Returns whether the coefficients are the line through r and p
+    fn addition_verify((alpha, bias): &(Fq2, Fq2), r: &mut G2Affine, p: &G2Affine) -> Choice {
         // y - alpha*x - bias =0
-        assert_eq!(Fq2::zero(), r.y - alpha.mul(&r.x) - bias);
-        assert_eq!(Fq2::zero(), p.y - alpha.mul(&p.x) - bias);
+        let r_on_line = (r.y - alpha.mul(&r.x) - bias).is_zero();
+        let p_on_line = (p.y - alpha.mul(&p.x) - bias).is_zero();
 
         //x3 = alpha^2-x1-x2
         let x3 = alpha.square() - r.x - p.x;
@@ -859,6 +1354,7 @@ pub fn multi_miller_loop_on_prove_pairing(
 
         r.x = x3;
         r.y = y3;
+        r_on_line & p_on_line
     }
 
     // coeffs:(alpha, bias)
@@ -875,7 +1371,9 @@ pub fn multi_miller_loop_on_prove_pairing(
         f.mul_by_034(&c0, &c1, &coeffs.1);
     }
 
-    let c_inv = c.invert().unwrap();
+    let c_inv = c.invert();
+    let mut valid = c_inv.is_some();
+    let c_inv = c_inv.unwrap_or(Fq12::one());
     let mut f = c_inv;
     let mut next_qs = init_q.clone();
     for i in (1..SIX_U_PLUS_2_NAF.len()).rev() {
@@ -892,7 +1390,7 @@ pub fn multi_miller_loop_on_prove_pairing(
 
         for ((p, coeffs), q) in pairs.iter_mut().zip(next_qs.iter_mut()) {
             let coeff = coeffs.next().unwrap();
-            double_verify(coeff, q);
+            valid &= double_verify(coeff, q);
             ell(&mut f, coeff, &p);
         }
 
@@ -902,7 +1400,7 @@ pub fn multi_miller_loop_on_prove_pairing(
                     pairs.iter_mut().zip(next_qs.iter_mut()).zip(init_q.iter())
                 {
                     let coeff = coeffs.next().unwrap();
-                    addition_verify(coeff, q, init_q);
+                    valid &= addition_verify(coeff, q, init_q);
                     ell(&mut f, coeff, &p);
                 }
             }
@@ -911,7 +1409,7 @@ pub fn multi_miller_loop_on_prove_pairing(
                     pairs.iter_mut().zip(next_qs.iter_mut()).zip(init_q.iter())
                 {
                     let coeff = coeffs.next().unwrap();
-                    addition_verify(coeff, q, &init_q.neg());
+                    valid &= addition_verify(coeff, q, &init_q.neg());
                     ell(&mut f, coeff, &p);
                 }
             }
@@ -943,7 +1441,7 @@ pub fn multi_miller_loop_on_prove_pairing(
         .zip(init_frobenius_q.iter())
     {
         let coeff = coeffs.next().unwrap();
-        addition_verify(coeff, q, &frobenius_q.0);
+        valid &= addition_verify(coeff, q, &frobenius_q.0);
         ell(&mut f, coeff, &p);
     }
 
@@ -953,15 +1451,14 @@ pub fn multi_miller_loop_on_prove_pairing(
         .zip(init_frobenius_q.iter())
     {
         let coeff = coeffs.next().unwrap();
-        addition_verify(coeff, q, &frobenius_q.1);
+        valid &= addition_verify(coeff, q, &frobenius_q.1);
         ell(&mut f, coeff, p);
     }
 
     for &mut (_p, ref mut coeffs) in &mut pairs {
         assert_eq!(coeffs.next(), None);
     }
-    assert_eq!(f, Fq12::one());
-    Gt(f)
+    CtOption::new(f, valid)
 }
 
 //on prove pairing take affine coordinate(slope,bias) calculation,
@@ -1028,9 +1525,16 @@ pub fn multi_miller_loop_on_prove_pairing_prepare(terms: &[(&G1Affine, &G2OnProv
     Gt(f)
 }
 
+pub fn multi_miller_loop_c_wi(c_gt: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
+    let f = miller_loop_c_wi(c_gt, wi, terms).unwrap();
+    assert_eq!(f, Fq12::one());
+    Gt(f)
+}
+
 //multi miller loop calculation with r-th residual parameters c&wi,the result should be 1
 //r=6x+2+p-p^2+p^3, f*wi will make sure f*wi is r-th residual
-pub fn multi_miller_loop_c_wi(c_gt: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
+//returns none if c is not invertible
+fn miller_loop_c_wi(c_gt: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2Prepared)]) -> CtOption<Fq12> {
     let c = c_gt.0;
     let mut pairs = vec![];
     for &(p, q) in terms {
@@ -1055,7 +1559,9 @@ pub fn multi_miller_loop_c_wi(c_gt: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2Prepar
     }
 
     // let mut f = Fq12::one();
-    let c_inv = c.invert().unwrap();
+    let c_inv = c.invert();
+    let valid = c_inv.is_some();
+    let c_inv = c_inv.unwrap_or(Fq12::one());
     let mut f = c_inv;
 
     for i in (1..SIX_U_PLUS_2_NAF.len()).rev() {
@@ -1118,8 +1624,12 @@ pub fn multi_miller_loop_c_wi(c_gt: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2Prepar
     for &mut (_p, ref mut coeffs) in &mut pairs {
         assert_eq!(coeffs.next(), None);
     }
-    assert_eq!(f, Fq12::one());
-    Gt(f)
+    CtOption::new(f, valid)
+}
+
+/// Checks whether the product of pairings of the given terms is the identity.
+pub fn pairing_check(terms: &[(&G1Affine, &G2Prepared)]) -> Choice {
+    multi_miller_loop(terms).is_one_after_final_exponentiation()
 }
 
 pub fn pairing(g1: &G1Affine, g2: &G2Affine) -> Gt {
@@ -1161,10 +1671,58 @@ impl Engine for Bn256 {
 
 impl MultiMillerLoop for Bn256 {
     type G2Prepared = G2Prepared;
+    type Result = Gt;
 
-    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Gt {
+    fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result {
         multi_miller_loop(terms)
     }
+
+    fn pairing_check(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Choice {
+        pairing_check(terms)
+    }
+}
+
+/// The Miller loop over `G2Prepared`, whose line functions are evaluated
+/// in projective coordinates.
+#[derive(Clone, Copy, Debug)]
+pub struct ProjectiveMillerLoop;
+
+impl MillerLoopStrategy<Bn256> for ProjectiveMillerLoop {
+    type G2Prepared = G2Prepared;
+
+    fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
+        multi_miller_loop(terms)
+    }
+
+    fn pairing_check_c_wi(c: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2Prepared)]) -> Choice {
+        miller_loop_c_wi(c, wi, terms)
+            .map(|f| f.ct_eq(&Fq12::one()))
+            .unwrap_or(Choice::from(0))
+    }
+}
+
+/// The Miller loop over `G2OnProvePrepared`, whose line functions are
+/// evaluated in affine coordinates.
+#[derive(Clone, Copy, Debug)]
+pub struct AffineMillerLoop;
+
+impl MillerLoopStrategy<Bn256> for AffineMillerLoop {
+    type G2Prepared = G2OnProvePrepared;
+
+    fn multi_miller_loop(terms: &[(&G1Affine, &G2OnProvePrepared)]) -> Gt {
+        multi_miller_loop_on_prove_pairing_prepare(terms)
+    }
+
+    fn pairing_check_c_wi(c: &Gt, wi: &Gt, terms: &[(&G1Affine, &G2OnProvePrepared)]) -> Choice {
+        miller_loop_on_prove_pairing_c_wi(c, wi, terms)
+            .map(|f| f.ct_eq(&Fq12::one()))
+            .unwrap_or(Choice::from(0))
+    }
+}
+
+impl MillerLoopStrategies for Bn256 {
+    type ProjectiveStrategy = ProjectiveMillerLoop;
+    type AffineStrategy = AffineMillerLoop;
 }
 
 impl MultiMillerLoopOnProvePairing for Bn256 {
@@ -1386,3 +1944,481 @@ fn random_miller_loop_tests() {
         assert_eq!(abcd, abcd_with_double_loop);
     }
 }
+
+// Straightforward final exponentiation kept as an oracle for the optimized one.
+#[cfg(test)]
+fn final_exponentiation_reference(f: &Fq12) -> Fq12 {
+    fn exp_by_x(f: &mut Fq12) {
+        let x = BN_X;
+        let mut res = Fq12::one();
+        for i in (0..64).rev() {
+            res.cyclotomic_square();
+            if ((x >> i) & 1) == 1 {
+                res.mul_assign(f);
+            }
+        }
+        *f = res;
+    }
+
+    let mut f1 = *f;
+    f1.conjugate();
+    let mut f2 = f.invert().unwrap();
+
+    let mut r = f1;
+    r.mul_assign(&f2);
+    f2 = r;
+    r.frobenius_map(2);
+    r.mul_assign(&f2);
+
+    let mut fp = r;
+    fp.frobenius_map(1);
+
+    let mut fp2 = r;
+    fp2.frobenius_map(2);
+    let mut fp3 = fp2;
+    fp3.frobenius_map(1);
+
+    let mut fu = r;
+    exp_by_x(&mut fu);
+
+    let mut fu2 = fu;
+    exp_by_x(&mut fu2);
+
+    let mut fu3 = fu2;
+    exp_by_x(&mut fu3);
+
+    let mut y3 = fu;
+    y3.frobenius_map(1);
+
+    let mut fu2p = fu2;
+    fu2p.frobenius_map(1);
+
+    let mut fu3p = fu3;
+    fu3p.frobenius_map(1);
+
+    let mut y2 = fu2;
+    y2.frobenius_map(2);
+
+    let mut y0 = fp;
+    y0.mul_assign(&fp2);
+    y0.mul_assign(&fp3);
+
+    let mut y1 = r;
+    y1.conjugate();
+
+    let mut y5 = fu2;
+    y5.conjugate();
+
+    y3.conjugate();
+
+    let mut y4 = fu;
+    y4.mul_assign(&fu2p);
+    y4.conjugate();
+
+    let mut y6 = fu3;
+    y6.mul_assign(&fu3p);
+    y6.conjugate();
+
+    y6.cyclotomic_square();
+    y6.mul_assign(&y4);
+    y6.mul_assign(&y5);
+
+    let mut t1 = y3;
+    t1.mul_assign(&y5);
+    t1.mul_assign(&y6);
+
+    y6.mul_assign(&y2);
+
+    t1.cyclotomic_square();
+    t1.mul_assign(&y6);
+    t1.cyclotomic_square();
+
+    let mut t0 = t1;
+    t0.mul_assign(&y1);
+
+    t1.mul_assign(&y0);
+
+    t0.cyclotomic_square();
+    t0.mul_assign(&t1);
+
+    t0
+}
+
+#[test]
+fn test_exp_by_x() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..20 {
+        let a = G1Affine::from(G1::random(&mut rng));
+        let b = G2Prepared::from(G2Affine::from(G2::random(&mut rng)));
+        let mut f = multi_miller_loop(&[(&a, &b)]).final_exponentiation().0;
+        let expected = f.pow_vartime([BN_X]);
+        exp_by_x(&mut f);
+        assert_eq!(f, expected);
+    }
+
+    let mut one = Fq12::one();
+    exp_by_x(&mut one);
+    assert_eq!(one, Fq12::one());
+}
+
+#[test]
+fn test_final_exponentiation_reference() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let f = Fq12::random(&mut rng);
+        assert_eq!(
+            Gt(f).final_exponentiation().0,
+            final_exponentiation_reference(&f)
+        );
+    }
+
+    for _ in 0..100 {
+        let a = G1Affine::from(G1::random(&mut rng));
+        let b = G2Prepared::from(G2Affine::from(G2::random(&mut rng)));
+        let f = multi_miller_loop(&[(&a, &b)]);
+        assert_eq!(
+            f.final_exponentiation().0,
+            final_exponentiation_reference(&f.0)
+        );
+    }
+}
+
+#[test]
+fn test_pairing_check() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let z1 = G1Affine::identity();
+    let z2 = G2Prepared::from(G2Affine::identity());
+    assert!(bool::from(Bn256::pairing_check(&[])));
+
+    for _ in 0..10 {
+        let p = G1::random(&mut rng);
+        let q = G2::random(&mut rng);
+        let s = Fr::random(&mut rng);
+
+        // e(sP, Q) * e(-P, sQ) = 1
+        let sp = G1Affine::from(p * s);
+        let neg_p = G1Affine::from(-p);
+        let q_prepared = G2Prepared::from(G2Affine::from(q));
+        let sq_prepared = G2Prepared::from(G2Affine::from(q * s));
+        assert!(bool::from(Bn256::pairing_check(&[
+            (&sp, &q_prepared),
+            (&neg_p, &sq_prepared)
+        ])));
+
+        // e(sP, Q) * e(P, sQ) != 1
+        let p = G1Affine::from(p);
+        assert!(!bool::from(Bn256::pairing_check(&[
+            (&sp, &q_prepared),
+            (&p, &sq_prepared)
+        ])));
+
+        // Identities on either side contribute nothing to the product
+        assert!(bool::from(Bn256::pairing_check(&[(&z1, &q_prepared)])));
+        assert!(bool::from(Bn256::pairing_check(&[(&p, &z2)])));
+        assert!(bool::from(Bn256::pairing_check(&[
+            (&sp, &q_prepared),
+            (&z1, &sq_prepared),
+            (&neg_p, &sq_prepared),
+            (&p, &z2)
+        ])));
+        assert!(!bool::from(Bn256::pairing_check(&[
+            (&sp, &q_prepared),
+            (&neg_p, &z2)
+        ])));
+        assert!(!bool::from(Bn256::pairing_check(&[
+            (&sp, &q_prepared),
+            (&z1, &sq_prepared)
+        ])));
+    }
+}
+
+#[cfg(feature = "multicore")]
+#[test]
+fn test_multi_miller_loop_parallel() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for n in [0, 1, 2, 7, 64] {
+        let g1: Vec<G1Affine> = (0..n)
+            .map(|i| {
+                if i % 5 == 3 {
+                    G1Affine::identity()
+                } else {
+                    G1Affine::from(G1::random(&mut rng))
+                }
+            })
+            .collect();
+        let g2: Vec<G2Prepared> = (0..n)
+            .map(|i| {
+                if i % 7 == 5 {
+                    G2Prepared::from(G2Affine::identity())
+                } else {
+                    G2Prepared::from(G2Affine::from(G2::random(&mut rng)))
+                }
+            })
+            .collect();
+        let terms: Vec<(&G1Affine, &G2Prepared)> = g1.iter().zip(g2.iter()).collect();
+
+        assert_eq!(
+            multi_miller_loop_parallel(&terms),
+            multi_miller_loop(&terms)
+        );
+    }
+}
+
+#[test]
+fn test_gt_generator() {
+    assert_eq!(
+        Gt::generator(),
+        pairing(&G1Affine::generator(), &G2Affine::generator())
+    );
+    assert!(!bool::from(Gt::generator().is_identity()));
+    assert!(bool::from(Gt::generator().is_in_subgroup()));
+}
+
+#[test]
+fn test_gt_random() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        let a = <Gt as Group>::random(&mut rng);
+        assert!(bool::from(a.is_in_subgroup()));
+        assert!(!bool::from(a.is_identity()));
+    }
+}
+
+#[test]
+fn test_gt_encoding() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for a in [
+        Gt::identity(),
+        Gt::generator(),
+        <Gt as Group>::random(&mut rng),
+    ] {
+        let bytes = a.to_bytes();
+        assert_eq!(Gt::from_bytes(&bytes).unwrap(), a);
+        assert_eq!(Gt::from_bytes_unchecked(&bytes).unwrap(), a);
+    }
+
+    // An element outside the r-torsion subgroup is rejected unless unchecked
+    let f = Gt(Fq12::random(&mut rng));
+    let bytes = f.to_bytes();
+    assert!(bool::from(Gt::from_bytes(&bytes).is_none()));
+    assert_eq!(Gt::from_bytes_unchecked(&bytes).unwrap(), f);
+
+    // Non-canonical coefficients are rejected
+    let mut bytes = Gt::generator().to_bytes();
+    bytes.as_mut()[224..256].copy_from_slice(&[0xff; 32]);
+    assert!(bool::from(Gt::from_bytes(&bytes).is_none()));
+    assert!(bool::from(Gt::from_bytes_unchecked(&bytes).is_none()));
+}
+
+#[test]
+fn test_gt_compression() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(GtCompressed::default().as_ref().len(), Fq12::size() / 3);
+
+    let identity = Gt::identity().compress();
+    assert_eq!(identity.as_ref()[127], GT_IDENTITY_FLAG);
+    assert_eq!(Gt::decompress(&identity).unwrap(), Gt::identity());
+
+    for _ in 0..10 {
+        let a = <Gt as Group>::random(&mut rng);
+        let compressed = a.compress();
+        assert_eq!(compressed.as_ref()[127] >> 6, 0);
+        assert_eq!(Gt::decompress(&compressed).unwrap(), a);
+        assert_eq!(Gt::decompress(&(-a).compress()).unwrap(), -a);
+    }
+
+    // Flags must match the payload
+    let mut bytes = Gt::generator().compress();
+    bytes.as_mut()[127] |= GT_IDENTITY_FLAG;
+    assert!(bool::from(Gt::decompress(&bytes).is_none()));
+    let mut bytes = Gt::generator().compress();
+    bytes.as_mut()[127] |= GT_G0_IS_ZERO_FLAG;
+    assert!(bool::from(Gt::decompress(&bytes).is_none()));
+    let mut bytes = identity;
+    bytes.as_mut()[127] |= GT_G0_IS_ZERO_FLAG;
+    assert!(bool::from(Gt::decompress(&bytes).is_none()));
+
+    // Non-canonical coordinates are rejected
+    let mut bytes = Gt::generator().compress();
+    bytes.as_mut()[32..64].copy_from_slice(&[0xff; 32]);
+    assert!(bool::from(Gt::decompress(&bytes).is_none()));
+
+    // Elements of the cyclotomic subgroup outside of Gt are rejected
+    let mut bytes = GtCompressed::default();
+    bytes.as_mut()[0..64].copy_from_slice(&Fq2::random(&mut rng).to_bytes());
+    bytes.as_mut()[64..128].copy_from_slice(&Fq2::random(&mut rng).to_bytes());
+    assert!(bool::from(Gt::decompress(&bytes).is_none()));
+}
+
+#[test]
+fn test_gt_is_in_subgroup() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // Fq12^* is cyclic, so the elements of order dividing r are exactly the
+    // order-r subgroup
+    let has_order_r = |f: &Fq12| f.pow_vartime(crate::bn256::fr::MODULUS.0) == Fq12::one();
+
+    assert!(bool::from(Gt::identity().is_in_subgroup()));
+    assert!(bool::from(Gt::generator().is_in_subgroup()));
+    assert!(!bool::from(Gt(Fq12::zero()).is_in_subgroup()));
+    assert!(!bool::from(Gt(-Fq12::one()).is_in_subgroup()));
+
+    for _ in 0..10 {
+        let a = <Gt as Group>::random(&mut rng);
+        assert!(has_order_r(&a.0));
+        assert!(bool::from(a.is_in_subgroup()));
+        assert_eq!(Gt::from_fq12(&a.0).unwrap(), a);
+
+        let f = Fq12::random(&mut rng);
+        assert!(!has_order_r(&f));
+        assert!(!bool::from(Gt(f).is_in_subgroup()));
+        assert!(bool::from(Gt::from_fq12(&f).is_none()));
+
+        // Cyclotomic but outside the order-r subgroup
+        let mut f_conj = f;
+        f_conj.conjugate();
+        let mut g = f_conj * f.invert().unwrap();
+        let g_p2 = {
+            let mut t = g;
+            t.frobenius_map(2);
+            t
+        };
+        g *= g_p2;
+        assert!(!has_order_r(&g));
+        assert!(!bool::from(Gt(g).is_in_subgroup()));
+    }
+}
+
+#[test]
+fn test_gt_glv_decompose() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let lambda = Fr::from(BN_X).square() * Fr::from(6);
+    let from_i128 = |a: i128| {
+        let a_abs = Fr::from_u128(a.unsigned_abs());
+        if a < 0 {
+            -a_abs
+        } else {
+            a_abs
+        }
+    };
+    for basis in GT_GLV_BASIS.iter() {
+        let acc = basis
+            .iter()
+            .rev()
+            .fold(Fr::zero(), |acc, a| acc * lambda + from_i128(*a));
+        assert_eq!(acc, Fr::zero());
+    }
+
+    let scalars = [Fr::zero(), Fr::one(), -Fr::one(), Fr::TWO_INV];
+    for k in scalars
+        .iter()
+        .cloned()
+        .chain((0..1000).map(|_| Fr::random(&mut rng)))
+    {
+        let ks = gt_glv_decompose(&k);
+        for k_i in ks.iter() {
+            assert!(k_i.unsigned_abs() < 1 << GT_GLV_BITS);
+        }
+        let acc = ks
+            .iter()
+            .rev()
+            .fold(Fr::zero(), |acc, k_i| acc * lambda + from_i128(*k_i));
+        assert_eq!(acc, k);
+    }
+}
+
+#[test]
+fn test_gt_pow() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let g = Gt::generator();
+    assert_eq!(g.pow(&Fr::zero()), Gt::identity());
+    assert_eq!(g.pow(&Fr::one()), g);
+    assert_eq!(g.pow(&-Fr::one()), -g);
+    assert_eq!(Gt::identity().pow(&Fr::random(&mut rng)), Gt::identity());
+
+    for _ in 0..5 {
+        let p = G1Affine::from(G1::random(&mut rng));
+        let q = G2Affine::from(G2::random(&mut rng));
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+
+        // e(aP, Q) = e(P, Q)^a = e(P, aQ)
+        let e = pairing(&p, &q);
+        let e_a = pairing(&G1Affine::from(p * a), &q);
+        assert_eq!(e.pow(&a), e_a);
+        assert_eq!(e * a, e_a);
+        assert_eq!(pairing(&p, &G2Affine::from(q * a)), e_a);
+        let a_limbs: Vec<u64> = a
+            .to_repr()
+            .chunks(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(e.pow(&a), Gt(e.0.pow_vartime(a_limbs)));
+
+        // The scalar multiplication agrees with the group law
+        assert_eq!(e * a + e * b, e * (a + b));
+        assert_eq!(e * a - e * b, e * (a - b));
+        assert_eq!((e * a) * b, e * (a * b));
+        assert_eq!(e + e, e.double());
+    }
+}
+
+#[test]
+fn test_gt_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Gt::identity().sqrt().unwrap(), Gt::identity());
+
+    for _ in 0..10 {
+        let a = <Gt as Group>::random(&mut rng);
+        let b = a.sqrt().unwrap();
+        assert_eq!(b.double(), a);
+        assert!(bool::from(b.is_in_subgroup()));
+        assert_eq!(a.double().sqrt().unwrap(), a);
+
+        // Only roots inside Gt are returned
+        assert!(bool::from(Gt(Fq12::random(&mut rng)).sqrt().is_none()));
+    }
+}
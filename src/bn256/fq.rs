@@ -1,8 +1,8 @@
 #[cfg(all(feature = "asm", target_arch = "x86_64"))]
 use super::assembly::assembly_field;
 use super::common::common_field;
-use super::LegendreSymbol;
-use crate::arithmetic::{adc, mac, sbb, BaseExt, FieldExt, Group};
+use crate::arithmetic::{adc, mac, safegcd_invert, sbb, BaseExt, FieldExt, Group, Legendre};
+use crate::hash_to_curve::HashToField;
 use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
@@ -57,13 +57,49 @@ pub const NEGATIVE_ONE: Fq = Fq([
     0x2259d6b14729c0fa,
 ]);
 
+/// (q + 1) / 4, the exponent of the square root as q = 3 mod 4
+/// 0xc19139cb84c680a6e14116da060561765e05aa45a1c72a34f082305b61f3f52
+const SQRT_EXP: [u64; 4] = [
+    0x4f082305b61f3f52,
+    0x65e05aa45a1c72a3,
+    0x6e14116da0605617,
+    0x0c19139cb84c680a,
+];
+
 const BASEEXT_MODULUS: &'static str =
     "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
 
-const TWO_INV: Fq = Fq::from_raw([0, 0, 0, 0]);
-const ROOT_OF_UNITY_INV: Fq = Fq::from_raw([0, 0, 0, 0]);
-const DELTA: Fq = Fq::from_raw([0, 0, 0, 0]);
-const ZETA: Fq = Fq::from_raw([0, 0, 0, 0]);
+const GENERATOR: Fq = Fq::from_raw([0x03, 0x00, 0x00, 0x00]);
+
+const S: u32 = 1;
+
+/// GENERATOR^((q - 1) / 2) = -1
+const ROOT_OF_UNITY: Fq = Fq::from_raw([
+    0x3c208c16d87cfd46,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+const TWO_INV: Fq = Fq::from_raw([
+    0x9e10460b6c3e7ea4,
+    0xcbc0b548b438e546,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+]);
+
+const ROOT_OF_UNITY_INV: Fq = ROOT_OF_UNITY;
+
+/// GENERATOR^(2^S)
+const DELTA: Fq = Fq::from_raw([0x09, 0x00, 0x00, 0x00]);
+
+// 0x30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd48
+const ZETA: Fq = Fq::from_raw([
+    0xe4bd44e5607cfd48,
+    0xc28f069fbb966e3d,
+    0x5e6dd9e7e0acccb0,
+    0x30644e72e131a029,
+]);
 
 impl_binops_additive!(Fq, Fq);
 impl_binops_multiplicative!(Fq, Fq);
@@ -130,23 +166,29 @@ impl Fq {
         res
     }
 
-    pub fn legendre(&self) -> LegendreSymbol {
-        // s = self^((modulus - 1) // 2)
-        // 0x183227397098d014dc2822db40c0ac2ecbc0b548b438e5469e10460b6c3e7ea3
-        let s = &[
-            0x9e10460b6c3e7ea3u64,
-            0xcbc0b548b438e546u64,
-            0xdc2822db40c0ac2eu64,
-            0x183227397098d014u64,
-        ];
-        let s = self.pow(s);
-        if s == Self::zero() {
-            LegendreSymbol::Zero
-        } else if s == Self::one() {
-            LegendreSymbol::QuadraticResidue
-        } else {
-            LegendreSymbol::QuadraticNonResidue
+    /// Computes the square root of this element in variable time. This must
+    /// only be used on public data, such as points of a proof.
+    pub fn sqrt_vartime(&self) -> CtOption<Self> {
+        let tmp = self.pow_vartime_sliding(&SQRT_EXP);
+
+        CtOption::new(tmp, tmp.square().ct_eq(self))
+    }
+}
+
+impl HashToField for Fq {
+    const UNIFORM_BYTES: usize = 48;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), Self::UNIFORM_BYTES);
+        let mut wide = [0u8; 64];
+        for (w, b) in wide.iter_mut().zip(bytes.iter().rev()) {
+            *w = *b;
         }
+        Fq::from_bytes_wide(&wide)
+    }
+
+    fn sgn0(&self) -> Choice {
+        Choice::from(self.to_bytes()[0] & 1)
     }
 }
 
@@ -184,12 +226,7 @@ impl ff::Field for Fq {
 
     /// Computes the square root of this element, if it exists.
     fn sqrt(&self) -> CtOption<Self> {
-        let tmp = self.pow(&[
-            0x4f082305b61f3f52,
-            0x65e05aa45a1c72a3,
-            0x6e14116da0605617,
-            0x0c19139cb84c680a,
-        ]);
+        let tmp = self.pow(&SQRT_EXP);
 
         CtOption::new(tmp, tmp.square().ct_eq(self))
     }
@@ -197,14 +234,7 @@ impl ff::Field for Fq {
     /// Computes the multiplicative inverse of this element,
     /// failing if the element is zero.
     fn invert(&self) -> CtOption<Self> {
-        let tmp = self.pow(&[
-            0x3c208c16d87cfd45,
-            0x97816a916871ca8d,
-            0xb85045b68181585d,
-            0x30644e72e131a029,
-        ]);
-
-        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+        self.invert_safegcd()
     }
 }
 
@@ -214,7 +244,7 @@ impl ff::PrimeField for Fq {
     const NUM_BITS: u32 = 254;
     const CAPACITY: u32 = 253;
 
-    const S: u32 = 0;
+    const S: u32 = S;
 
     fn from_repr(repr: Self::Repr) -> CtOption<Self> {
         let mut tmp = Fq([0, 0, 0, 0]);
@@ -266,15 +296,14 @@ impl ff::PrimeField for Fq {
     }
 
     fn multiplicative_generator() -> Self {
-        unimplemented!()
+        GENERATOR
     }
 
     fn root_of_unity() -> Self {
-        unimplemented!()
+        ROOT_OF_UNITY
     }
 }
 
-
 #[cfg(feature = "gpu")]
 impl ec_gpu::GpuName for Fq {
     fn name() -> String {
@@ -297,6 +326,8 @@ impl ec_gpu::GpuField for Fq {
     }
 }
 
+#[cfg(test)]
+use super::LegendreSymbol;
 #[cfg(test)]
 use ff::Field;
 #[cfg(test)]
@@ -354,6 +385,62 @@ pub fn test_sqrt() {
     }
 }
 
+#[test]
+fn test_sqrt_vartime() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let a = Fq::random(&mut rng);
+        let e = [
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+            rng.next_u64(),
+        ];
+        assert_eq!(a.pow_vartime_sliding(&e), a.pow(&e));
+
+        let sqrt = a.sqrt();
+        let sqrt_vartime = a.sqrt_vartime();
+        assert_eq!(
+            bool::from(sqrt.is_some()),
+            bool::from(sqrt_vartime.is_some())
+        );
+        if bool::from(sqrt.is_some()) {
+            assert_eq!(sqrt.unwrap(), sqrt_vartime.unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_legendre() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fq::zero().legendre(), LegendreSymbol::Zero);
+    assert_eq!(Fq::one().legendre(), LegendreSymbol::QuadraticResidue);
+    assert_eq!(NEGATIVE_ONE.legendre(), LegendreSymbol::QuadraticNonResidue);
+
+    for _ in 0..1000 {
+        let a = Fq::random(&mut rng);
+        // Euler's criterion, a^((q - 1) / 2)
+        let euler = a.pow_vartime(&[
+            0x9e10460b6c3e7ea3,
+            0xcbc0b548b438e546,
+            0xdc2822db40c0ac2e,
+            0x183227397098d014,
+        ]);
+        let expected = if euler == Fq::one() { 1 } else { -1 };
+        assert_eq!(a.jacobi(), expected);
+        assert_eq!(bool::from(a.ct_quadratic_non_residue()), expected == -1);
+        assert_eq!((a.square() * NEGATIVE_ONE).jacobi(), -1);
+    }
+}
+
 #[test]
 fn test_from_u512() {
     assert_eq!(
@@ -376,6 +463,45 @@ fn test_from_u512() {
     );
 }
 
+#[test]
+fn test_zeta() {
+    let a = Fq::ZETA;
+    assert!(a != Fq::one());
+    let b = a * a;
+    assert!(b != Fq::one());
+    let c = b * a;
+    assert!(c == Fq::one());
+}
+
+#[test]
+fn test_root_of_unity() {
+    assert_eq!(Fq::root_of_unity(), NEGATIVE_ONE);
+    assert_eq!(
+        Fq::root_of_unity().pow_vartime(&[1 << Fq::S, 0, 0, 0]),
+        Fq::one()
+    );
+    assert_eq!(
+        Fq::multiplicative_generator().pow_vartime(&[
+            0x9e10460b6c3e7ea3,
+            0xcbc0b548b438e546,
+            0xdc2822db40c0ac2e,
+            0x183227397098d014,
+        ]),
+        Fq::root_of_unity()
+    );
+    assert_eq!(Fq::DELTA, Fq::multiplicative_generator().square());
+}
+
+#[test]
+fn test_inv_root_of_unity() {
+    assert_eq!(Fq::ROOT_OF_UNITY_INV, Fq::root_of_unity().invert().unwrap());
+}
+
+#[test]
+fn test_inv_2() {
+    assert_eq!(Fq::TWO_INV, Fq::from(2).invert().unwrap());
+}
+
 #[test]
 fn test_field() {
     crate::tests::field::random_field_tests::<Fq>("fq".to_string());
@@ -1,8 +1,11 @@
 use super::fq::Fq;
 use super::fq2::Fq2;
 use super::fq6::Fq6;
+use core::convert::TryInto;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
+use num_bigint::BigUint;
+use num_traits::Zero;
 use rand::RngCore;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -76,6 +79,33 @@ impl_binops_additive!(Fq12, Fq12);
 impl_binops_multiplicative!(Fq12, Fq12);
 
 impl Fq12 {
+    pub const fn size() -> usize {
+        384
+    }
+
+    /// Attempts to convert a little-endian byte representation of
+    /// an element into a `Fq12`, failing if any coefficient is not canonical.
+    pub fn from_bytes(bytes: &[u8; 384]) -> CtOption<Fq12> {
+        let c0 = Fq6::from_bytes(bytes[0..192].try_into().unwrap());
+        let c1 = Fq6::from_bytes(bytes[192..384].try_into().unwrap());
+        CtOption::new(
+            Fq12 {
+                c0: c0.unwrap_or(Fq6::zero()),
+                c1: c1.unwrap_or(Fq6::zero()),
+            },
+            c0.is_some() & c1.is_some(),
+        )
+    }
+
+    /// Converts an element of `Fq12` into a byte representation in
+    /// little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 384] {
+        let mut res = [0u8; 384];
+        res[0..192].copy_from_slice(&self.c0.to_bytes()[..]);
+        res[192..384].copy_from_slice(&self.c1.to_bytes()[..]);
+        res
+    }
+
     pub fn mul_assign(&mut self, other: &Self) {
         let t0 = self.c0 * other.c0;
         let mut t1 = self.c1 * other.c1;
@@ -269,6 +299,233 @@ impl Fq12 {
         t2.double_assign();
         self.c0.c2 = t2 + t5;
     }
+
+    /// Squares an element of the cyclotomic subgroup kept in Karabina's
+    /// compressed form (https://eprint.iacr.org/2010/542.pdf). Only `c0.c1`,
+    /// `c0.c2`, `c1.c0` and `c1.c2` are tracked; `c0.c0` and `c1.c1` are
+    /// garbage until the element is decompressed.
+    pub fn compressed_cyclotomic_square(&mut self) {
+        // g1^2, g5^2 and 2 * g1 * g5
+        let g1g1 = self.c0.c1.square();
+        let g5g5 = self.c1.c2.square();
+        let g1g5 = (self.c0.c1 + self.c1.c2).square() - g1g1 - g5g5;
+
+        // g2^2, g3^2 and 2 * g2 * g3
+        let g2g2 = self.c0.c2.square();
+        let g3g3 = self.c1.c0.square();
+        let g2g3 = (self.c1.c0 + self.c0.c2).square() - g2g2 - g3g3;
+
+        // h3 = 6 * xi * g1 * g5 + 2 * g3
+        let mut t0 = g1g5;
+        t0.mul_by_nonresidue();
+        let mut t1 = t0 + self.c1.c0;
+        t1.double_assign();
+        self.c1.c0 = t1 + t0;
+
+        // h2 = 3 * (xi * g5^2 + g1^2) - 2 * g2
+        let mut t0 = g5g5;
+        t0.mul_by_nonresidue();
+        t0 += g1g1;
+        let mut t1 = t0 - self.c0.c2;
+        t1.double_assign();
+        self.c0.c2 = t1 + t0;
+
+        // h1 = 3 * (g3^2 + xi * g2^2) - 2 * g1
+        let mut t0 = g2g2;
+        t0.mul_by_nonresidue();
+        t0 += g3g3;
+        let mut t1 = t0 - self.c0.c1;
+        t1.double_assign();
+        self.c0.c1 = t1 + t0;
+
+        // h5 = 6 * g2 * g3 + 2 * g5
+        let mut t1 = g2g3 + self.c1.c2;
+        t1.double_assign();
+        self.c1.c2 = t1 + g2g3;
+    }
+
+    /// Recovers `c0.c0` and `c1.c1` of an element squared with
+    /// `compressed_cyclotomic_square`.
+    pub fn decompress(&mut self) {
+        Self::batch_decompress(core::slice::from_mut(self));
+    }
+
+    /// Decompresses a batch of elements squared with
+    /// `compressed_cyclotomic_square`, sharing a single inversion.
+    pub fn batch_decompress(elements: &mut [Fq12]) {
+        // g4 = num / den, where
+        // num = xi * g5^2 + 3 * g1^2 - 2 * g2, den = 4 * g3  if g3 != 0
+        // num = 2 * g1 * g5,                   den = g2      otherwise
+        let mut nums = Vec::with_capacity(elements.len());
+        let mut dens = Vec::with_capacity(elements.len());
+        for g in elements.iter() {
+            let g3_is_zero = g.c1.c0.is_zero();
+
+            let g1g1 = g.c0.c1.square();
+            let mut num = g.c1.c2.square();
+            num.mul_by_nonresidue();
+            let mut t = g1g1 - g.c0.c2;
+            t.double_assign();
+            num += t + g1g1;
+            let mut den = g.c1.c0.double();
+            den.double_assign();
+
+            let num = Fq2::conditional_select(&num, &(g.c0.c1 * g.c1.c2).double(), g3_is_zero);
+            let den = Fq2::conditional_select(&den, &g.c0.c2, g3_is_zero);
+            nums.push(num);
+            dens.push(den);
+        }
+
+        // Montgomery's trick; a zero denominator only occurs for the
+        // identity, where g4 is zero as well
+        let mut acc = Fq2::one();
+        let mut prefix = Vec::with_capacity(dens.len());
+        for den in dens.iter() {
+            prefix.push(acc);
+            acc = Fq2::conditional_select(&(acc * den), &acc, den.is_zero());
+        }
+        let mut inv = acc.invert().unwrap();
+        for (den, prefix) in dens.iter_mut().zip(prefix.into_iter()).rev() {
+            let is_zero = den.is_zero();
+            let den_inv = inv * prefix;
+            inv = Fq2::conditional_select(&(inv * *den), &inv, is_zero);
+            *den = Fq2::conditional_select(&den_inv, &Fq2::zero(), is_zero);
+        }
+
+        for ((g, num), den_inv) in elements.iter_mut().zip(nums.iter()).zip(dens.iter()) {
+            // g4
+            g.c1.c1 = num * den_inv;
+
+            // g0 = xi * (2 * g4^2 + g3 * g5 - 3 * g1 * g2) + 1
+            let g1g2 = g.c0.c1 * g.c0.c2;
+            let mut t = g.c1.c1.square() - g1g2;
+            t.double_assign();
+            t -= g1g2;
+            t += g.c1.c0 * g.c1.c2;
+            t.mul_by_nonresidue();
+            g.c0.c0 = t + Fq2::one();
+        }
+    }
+
+    /// Returns the norm of this element to Fq, the product of its conjugates.
+    pub fn norm(&self) -> Fq {
+        // Conjugates by q^0, q^6
+        let mut t = *self;
+        t.frobenius_map(6);
+        let mut n = *self * t;
+        // q^0, q^3, q^6, q^9
+        let mut t = n;
+        t.frobenius_map(3);
+        n *= t;
+        // all of q^0, ..., q^11
+        let mut t1 = n;
+        t1.frobenius_map(1);
+        let mut t2 = t1;
+        t2.frobenius_map(1);
+        (n * t1 * t2).c0.c0.c0
+    }
+
+    /// Returns whether this element is a cube. Zero is a cube.
+    ///
+    /// Since $3 | q - 1$, $a$ is a cube iff $a^{(q^{12} - 1) / 3} = 1$, that is
+    /// iff its norm $a^{(q^{12} - 1) / (q - 1)}$ is a cube in Fq.
+    pub fn is_cubic_residue(&self) -> Choice {
+        self.norm()
+            .pow_vartime(CUBIC_RESIDUE_EXPONENT)
+            .ct_eq(&Fq::one())
+            | self.is_zero()
+    }
+
+    /// Returns a cube root of this element, if it is a cube.
+    ///
+    /// This is the cubic variant of Tonelli-Shanks by Adleman, Manders and
+    /// Miller. Write $q^{12} - 1 = 3^3 t$, where $t = 2 \bmod 3$. For a cube
+    /// $a$, $u = a^t$ lies in the subgroup of order 9 generated by
+    /// $\zeta^3$, $\zeta$ being of order 27, so $u = \zeta^{3j}$ for some $j$.
+    /// As $3k = 1 + t$ with $k = (t + 1) / 3$, $(a^k \zeta^{-j})^3 = a$.
+    pub fn cube_root(&self) -> CtOption<Fq12> {
+        let u = self.pow_vartime(CUBE_ROOT_T);
+
+        // Find j by trying all of them
+        let zeta_inv = CUBE_ROOT_ZETA.invert().unwrap();
+        let zeta_cube = CUBE_ROOT_ZETA.square() * CUBE_ROOT_ZETA;
+        let (mut zeta_inv_j, mut zeta_cube_j) = (Fq12::one(), Fq12::one());
+        let mut y_inv = Fq12::one();
+        let mut is_cube = self.is_zero();
+        for _ in 0..9 {
+            let is_j = zeta_cube_j.ct_eq(&u);
+            y_inv.conditional_assign(&zeta_inv_j, is_j);
+            is_cube |= is_j;
+            zeta_inv_j *= zeta_inv;
+            zeta_cube_j *= zeta_cube;
+        }
+
+        CtOption::new(self.pow_vartime(CUBE_ROOT_K) * y_inv, is_cube)
+    }
+
+    /// Returns an n-th root of this element, if it is an n-th power, with the
+    /// exponents of `n` precomputed by [`NthRootExponents::new`].
+    ///
+    /// The root is none if `n` is not supported, see [`NthRootExponents`].
+    pub fn nth_root(&self, n: &NthRootExponents) -> CtOption<Fq12> {
+        CtOption::new(
+            self.pow_vartime(&n.inverse),
+            (self.pow_vartime(&n.order).ct_eq(&Fq12::one()) | self.is_zero())
+                & Choice::from(n.is_supported as u8),
+        )
+    }
+}
+
+/// The exponents taking n-th roots in Fq12 for a fixed `n`.
+///
+/// With $g = \gcd(n, q^{12} - 1)$, the n-th powers are the subgroup of
+/// order $m = (q^{12} - 1) / g$, and the root is taken in that subgroup as
+/// $a^{n^{-1} \bmod m}$. This requires $\gcd(n, m) = 1$, which holds e.g.
+/// for the group order r or for $n$ coprime to $q^{12} - 1$, but not for
+/// even $n$ or multiples of 3, for which there are `sqrt` and `cube_root`.
+#[derive(Clone, Debug)]
+pub struct NthRootExponents {
+    // n^-1 mod m
+    inverse: Vec<u64>,
+    // m, the order of the n-th powers
+    order: Vec<u64>,
+    is_supported: bool,
+}
+
+impl NthRootExponents {
+    /// Computes the exponents for `n`, given as little-endian limbs. If
+    /// $n = 0$ or $\gcd(n, m) \neq 1$, `Fq12::nth_root` always returns none.
+    pub fn new<S: AsRef<[u64]>>(n: S) -> Self {
+        fn from_limbs(limbs: &[u64]) -> BigUint {
+            limbs
+                .iter()
+                .rev()
+                .fold(BigUint::zero(), |acc, limb| (acc << 64) + *limb)
+        }
+
+        let n = from_limbs(n.as_ref());
+        let order = from_limbs(&CUBE_ROOT_T) * 27u64;
+        let (mut g, mut t) = (order.clone(), n.clone());
+        while !t.is_zero() {
+            let r = &g % &t;
+            g = t;
+            t = r;
+        }
+        let m = &order / &g;
+
+        match (&n % &m).modinv(&m).filter(|_| !n.is_zero()) {
+            Some(inverse) => NthRootExponents {
+                inverse: inverse.to_u64_digits(),
+                order: m.to_u64_digits(),
+                is_supported: true,
+            },
+            None => NthRootExponents {
+                inverse: vec![],
+                order: vec![],
+                is_supported: false,
+            },
+        }
+    }
 }
 
 impl Field for Fq12 {
@@ -306,7 +563,52 @@ impl Field for Fq12 {
     }
 
     fn sqrt(&self) -> CtOption<Self> {
-        unimplemented!()
+        // Complex method for the quadratic extension Fq12 = Fq6[w], w^2 = v:
+        // a = a0 + a1 w is a square iff its norm a0^2 - v a1^2 is a square in
+        // Fq6, see Algorithm 8 in https://eprint.iacr.org/2012/685.pdf
+        let two_inv = Fq6 {
+            c0: Fq2 {
+                c0: TWO_INV,
+                c1: Fq::zero(),
+            },
+            c1: Fq2::zero(),
+            c2: Fq2::zero(),
+        };
+
+        if self.c1.is_zero().into() {
+            // sqrt(a0), or sqrt(a0 / v) w when a0 is not a square in Fq6
+            return self
+                .c0
+                .sqrt()
+                .map(|c0| Fq12 {
+                    c0,
+                    c1: Fq6::zero(),
+                })
+                .or_else(|| {
+                    let mut v = Fq6::one();
+                    v.mul_by_nonresidue();
+                    (self.c0 * v.invert().unwrap()).sqrt().map(|c1| Fq12 {
+                        c0: Fq6::zero(),
+                        c1,
+                    })
+                });
+        }
+
+        let mut c1_v = self.c1.square();
+        c1_v.mul_by_nonresidue();
+        (self.c0.square() - c1_v).sqrt().and_then(|alpha| {
+            // Exactly one of (a0 + alpha) / 2 and (a0 - alpha) / 2 is a
+            // square, and it is nonzero since a1 is
+            ((self.c0 + alpha) * two_inv)
+                .sqrt()
+                .or_else(|| ((self.c0 - alpha) * two_inv).sqrt())
+                .and_then(|x0| {
+                    (x0 + x0).invert().map(|t| Fq12 {
+                        c0: x0,
+                        c1: self.c1 * t,
+                    })
+                })
+        })
     }
 
     fn invert(&self) -> CtOption<Self> {
@@ -314,6 +616,168 @@ impl Field for Fq12 {
     }
 }
 
+// (q - 1) / 3
+const CUBIC_RESIDUE_EXPONENT: [u64; 4] = [
+    0x69602eb24829a9c2,
+    0xdd2b2385cd7b4384,
+    0xe81ac1e7808072c9,
+    0x10216f7ba065e00d,
+];
+
+// t = (q^12 - 1) / 27
+const CUBE_ROOT_T: [u64; 48] = [
+    0x5307e27995b92560,
+    0x4e49724fd0d5baa9,
+    0x699f06918df573bd,
+    0xd8b67dadeb715c81,
+    0x7eb4df70066ad837,
+    0x8bc60a073f435457,
+    0xb34fb1760d3f4978,
+    0xbc5d7d832a5a7859,
+    0x13b9fe9c01a50908,
+    0x25f6820b2785c585,
+    0x60057134065445ab,
+    0x1fc0c463ca8a2bd1,
+    0x3ff6a767828803c5,
+    0xde07932f609ab2ad,
+    0x4c0927dc736cfc50,
+    0x094bd8c57a3a1975,
+    0xc93fc636a320419f,
+    0x4816f54c4aeea6f3,
+    0x9dcf8b6a6cbf2aad,
+    0x27650a02e1159634,
+    0x12cdc5abb33fb41f,
+    0xb10670d4c9fa651d,
+    0x94f26b298348f000,
+    0xe245ed77e6c4d4f7,
+    0x82b650ceb16b5719,
+    0x5f3b48372170fddb,
+    0x99c8d94054571dbf,
+    0x695db21f68b0ca80,
+    0xd8275f8bd582764a,
+    0xbcd6cd3562db09b2,
+    0xc81781459b8beb36,
+    0x4d18b942febb300a,
+    0xb0c86ca70ae44c2c,
+    0xff09759a58db3b11,
+    0x6fbe2f3e60f4945e,
+    0x0a23fd5ed8b291a6,
+    0xa808c1d6d2297805,
+    0x9f82d987285d4b9d,
+    0xad8b25ab34ab649b,
+    0xe5a1c5bf2788a521,
+    0x8557f3603dcda1ba,
+    0xe66ef786062352c3,
+    0x6f7a532371ba73e1,
+    0x32387421293acf26,
+    0x8974500aff1c98cd,
+    0x27956de909482a44,
+    0x9b104126d2a0a598,
+    0x0000000054c40524,
+];
+
+// (t + 1) / 3, so that 3k = 1 mod t
+const CUBE_ROOT_K: [u64; 48] = [
+    0x7102a0d331e861cb,
+    0x1a187b6ff0473e38,
+    0xcddfacdb2f51d13f,
+    0x483cd48f4e7b1ed5,
+    0xd4e6f5255778f2bd,
+    0x83ecae026a6bc6c7,
+    0x911a907caf15187d,
+    0xe9747f2bb8c8d2c8,
+    0x069354deab370302,
+    0x61fcd603b7d741d7,
+    0xcaac7b1157716c8e,
+    0xb540417698d8b945,
+    0x6aa78d2280d80141,
+    0x4a028665203390e4,
+    0x6eadb7f42679a970,
+    0x586e9d9728be087c,
+    0xedbfecbce10ac08a,
+    0x1807a7196e4f8cfb,
+    0xdf452e78ceea638f,
+    0xb7cc58aba05c8766,
+    0xb0ef41e3e66a915f,
+    0x3b02259c43537709,
+    0x31a623b881185000,
+    0x4b6ca47d4cec46fd,
+    0xd63cc59a3b23c7b3,
+    0x7513c2bd0b25a9f3,
+    0xdded9dc01c1d09ea,
+    0xcdc9e60a783aee2a,
+    0x9d62752e9c80d218,
+    0x944799bc7649033b,
+    0xed5d2b1733d94e67,
+    0x19b2e86baa3e6558,
+    0xe5982437ae4c1964,
+    0xffadd1de1d9e6905,
+    0x253f6514cafc3174,
+    0x58b6a9ca483b85e2,
+    0xe2ad95f2460dd2ac,
+    0x8a80f32d0d746e89,
+    0xe483b739118e76de,
+    0x4c8b41ea6282e1b5,
+    0x81c7fbcabf448b3e,
+    0x4ccfa7d757611b96,
+    0x2528c66125e8d14b,
+    0x6612d16063139a62,
+    0xd87c1aae55098844,
+    0x628724a303180e16,
+    0x33b015b79b8ae1dd,
+    0x000000001c41570c,
+];
+
+// c^t for the cubic nonresidue c = w, of order 27
+const CUBE_ROOT_ZETA: Fq12 = Fq12 {
+    c0: Fq6 {
+        c0: Fq2 {
+            c0: Fq([0, 0, 0, 0]),
+            c1: Fq([0, 0, 0, 0]),
+        },
+        c1: Fq2 {
+            c0: Fq([
+                0xff9c71633a90f775,
+                0xf1d42cb3f3f8d64d,
+                0xee85825477f3a059,
+                0x275b1a9da05b35cb,
+            ]),
+            c1: Fq([
+                0xdfd0bed582fbd56c,
+                0x7ef7d37314a57f39,
+                0x6b9a7801bc66a9fd,
+                0x0e927894f2f3d179,
+            ]),
+        },
+        c2: Fq2 {
+            c0: Fq([0, 0, 0, 0]),
+            c1: Fq([0, 0, 0, 0]),
+        },
+    },
+    c1: Fq6 {
+        c0: Fq2 {
+            c0: Fq([0, 0, 0, 0]),
+            c1: Fq([0, 0, 0, 0]),
+        },
+        c1: Fq2 {
+            c0: Fq([0, 0, 0, 0]),
+            c1: Fq([0, 0, 0, 0]),
+        },
+        c2: Fq2 {
+            c0: Fq([0, 0, 0, 0]),
+            c1: Fq([0, 0, 0, 0]),
+        },
+    },
+};
+
+// 2^-1
+const TWO_INV: Fq = Fq([
+    0x87bee7d24f060572,
+    0xd0fd2add2f1c6ae5,
+    0x8f5f7492fcfd4f44,
+    0x1f37631a3d9cbfac,
+]);
+
 // non_residue^((modulus^i-1)/6) for i=0,...,11
 pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     // Fq2(u + 1)**(((q^0) - 1) / 6)
@@ -584,3 +1048,226 @@ fn test_frobenius() {
 fn test_field() {
     crate::tests::field::random_field_tests::<Fq12>("fq12".to_string());
 }
+
+#[cfg(test)]
+fn random_cyclotomic<R: RngCore>(rng: &mut R) -> Fq12 {
+    // f^((p^6 - 1)(p^2 + 1))
+    let f = Fq12::random(rng);
+    let mut t = f;
+    t.conjugate();
+    t.mul_assign(&f.invert().unwrap());
+    let mut u = t;
+    u.frobenius_map(2);
+    t.mul_assign(&u);
+    t
+}
+
+#[test]
+fn test_compressed_cyclotomic_square() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for n in 1..50 {
+        let a = random_cyclotomic(&mut rng);
+        let mut b = a;
+        let mut c = a;
+        for _ in 0..n {
+            b.cyclotomic_square();
+            c.compressed_cyclotomic_square();
+        }
+        c.decompress();
+        assert_eq!(b, c);
+    }
+
+    let mut one = Fq12::one();
+    for _ in 0..10 {
+        one.compressed_cyclotomic_square();
+    }
+    one.decompress();
+    assert_eq!(one, Fq12::one());
+}
+
+#[test]
+fn test_batch_decompress() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let mut expected = vec![];
+    let mut compressed = vec![];
+    for i in 0..10 {
+        let mut a = if i == 4 {
+            Fq12::one()
+        } else {
+            random_cyclotomic(&mut rng)
+        };
+        let mut b = a;
+        for _ in 0..(i + 1) {
+            a.cyclotomic_square();
+            b.compressed_cyclotomic_square();
+        }
+        expected.push(a);
+        compressed.push(b);
+    }
+
+    Fq12::batch_decompress(&mut compressed);
+    assert_eq!(expected, compressed);
+}
+
+#[test]
+fn test_fq12_bytes() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let a = Fq12::random(&mut rng);
+        assert_eq!(Fq12::from_bytes(&a.to_bytes()).unwrap(), a);
+    }
+
+    let mut bytes = Fq12::one().to_bytes();
+    bytes[352..384].copy_from_slice(&[0xff; 32]);
+    assert!(bool::from(Fq12::from_bytes(&bytes).is_none()));
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fq12::zero().sqrt().unwrap(), Fq12::zero());
+    assert_eq!(TWO_INV + TWO_INV, Fq::one());
+
+    // Find a nonresidue by trial
+    let nonresidue = loop {
+        let a = Fq12::random(&mut rng);
+        if bool::from(a.sqrt().is_none()) {
+            break a;
+        }
+    };
+
+    for _ in 0..50 {
+        let a = Fq12::random(&mut rng);
+        let b = a.square();
+        let c = b.sqrt().unwrap();
+        assert!(c == a || c == -a);
+        assert!(bool::from((b * nonresidue).sqrt().is_none()));
+
+        let d = Fq12::random(&mut rng);
+        if let Some(e) = Option::<Fq12>::from(d.sqrt()) {
+            assert_eq!(e.square(), d);
+        } else {
+            assert!(bool::from((d * nonresidue).sqrt().is_some()));
+        }
+    }
+
+    // Elements of Fq6, square or not in Fq6, are squares in Fq12
+    for _ in 0..50 {
+        let a = Fq12 {
+            c0: Fq6::random(&mut rng),
+            c1: Fq6::zero(),
+        };
+        assert_eq!(a.sqrt().unwrap().square(), a);
+        let a = Fq12 {
+            c0: Fq6::zero(),
+            c1: Fq6::random(&mut rng),
+        };
+        let b = a.square();
+        let c = b.sqrt().unwrap();
+        assert!(c == a || c == -a);
+    }
+}
+
+#[test]
+fn test_cube_root() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // CUBE_ROOT_ZETA is w^t and has order 27
+    let w = Fq12 {
+        c0: Fq6::zero(),
+        c1: Fq6::one(),
+    };
+    assert!(!bool::from(w.is_cubic_residue()));
+    assert_eq!(w.pow_vartime(CUBE_ROOT_T), CUBE_ROOT_ZETA);
+    assert_ne!(CUBE_ROOT_ZETA.pow_vartime([9u64]), Fq12::one());
+    assert_eq!(CUBE_ROOT_ZETA.pow_vartime([27u64]), Fq12::one());
+    assert!(!bool::from(CUBE_ROOT_ZETA.is_cubic_residue()));
+    assert!(bool::from(
+        CUBE_ROOT_ZETA.pow_vartime([3u64]).is_cubic_residue()
+    ));
+
+    assert!(bool::from(Fq12::zero().is_cubic_residue()));
+    assert_eq!(Fq12::zero().cube_root().unwrap(), Fq12::zero());
+    assert_eq!(
+        Fq12::one().cube_root().unwrap().pow_vartime([3u64]),
+        Fq12::one()
+    );
+
+    let mut cubes = 0;
+    for _ in 0..30 {
+        let a = Fq12::random(&mut rng);
+        let b = a.square() * a;
+        assert!(bool::from(b.is_cubic_residue()));
+        assert_eq!(b.cube_root().unwrap().pow_vartime([3u64]), b);
+        assert!(!bool::from((b * w).is_cubic_residue()));
+        assert!(bool::from((b * w).cube_root().is_none()));
+
+        let c = Fq12::random(&mut rng);
+        let is_cube = bool::from(c.is_cubic_residue());
+        assert_eq!(bool::from(c.cube_root().is_some()), is_cube);
+        if is_cube {
+            assert_eq!(c.cube_root().unwrap().pow_vartime([3u64]), c);
+            cubes += 1;
+        }
+    }
+    assert!(cubes > 0 && cubes < 30);
+}
+
+#[test]
+fn test_nth_root() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // r divides q^12 - 1 once
+    let r = [
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+    // coprime to q^12 - 1
+    let n = [0x1234567890abcdef, 0x1, 0x0, 0x0];
+
+    let (r_exponents, n_exponents) = (NthRootExponents::new(r), NthRootExponents::new(n));
+
+    assert_eq!(Fq12::zero().nth_root(&r_exponents).unwrap(), Fq12::zero());
+    for _ in 0..10 {
+        let a = Fq12::random(&mut rng);
+
+        let b = a.pow_vartime(r);
+        assert_eq!(b.nth_root(&r_exponents).unwrap().pow_vartime(r), b);
+        assert!(bool::from(a.nth_root(&r_exponents).is_none()));
+
+        assert_eq!(a.nth_root(&n_exponents).unwrap().pow_vartime(n), a);
+    }
+}
+
+#[test]
+fn test_nth_root_unsupported() {
+    for n in [[0u64], [3u64], [6u64]] {
+        let n = NthRootExponents::new(n);
+        assert!(bool::from(Fq12::one().nth_root(&n).is_none()));
+        assert!(bool::from(Fq12::zero().nth_root(&n).is_none()));
+    }
+}
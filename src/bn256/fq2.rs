@@ -1,6 +1,6 @@
 use super::fq::{Fq, NEGATIVE_ONE};
-use super::LegendreSymbol;
-use crate::arithmetic::BaseExt;
+use crate::arithmetic::{BaseExt, Legendre};
+use crate::hash_to_curve::HashToField;
 use core::convert::TryInto;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
@@ -128,8 +128,8 @@ impl Fq2 {
         let c1 = Fq::from_bytes(bytes[32..64].try_into().unwrap());
         CtOption::new(
             Fq2 {
-                c0: c0.unwrap(),
-                c1: c1.unwrap(),
+                c0: c0.unwrap_or(Fq::zero()),
+                c1: c1.unwrap_or(Fq::zero()),
             },
             c0.is_some() & c1.is_some(),
         )
@@ -146,8 +146,66 @@ impl Fq2 {
         res
     }
 
-    pub fn legendre(&self) -> LegendreSymbol {
-        self.norm().legendre()
+    /// Computes the square root of this element in variable time. This must
+    /// only be used on public data, such as points of a proof.
+    pub fn sqrt_vartime(&self) -> CtOption<Self> {
+        self.sqrt_with(<Fq2 as BaseExt>::pow_vartime_sliding)
+    }
+
+    fn sqrt_with(&self, pow: impl Fn(&Fq2, &[u64; 4]) -> Fq2) -> CtOption<Self> {
+        // Algorithm 9, https://eprint.iacr.org/2012/685.pdf
+
+        if self.is_zero().into() {
+            CtOption::new(Self::zero(), Choice::from(1))
+        } else {
+            // a1 = self^((q - 3) / 4)
+            // 0xc19139cb84c680a6e14116da060561765e05aa45a1c72a34f082305b61f3f51
+            let u: [u64; 4] = [
+                0x4f082305b61f3f51,
+                0x65e05aa45a1c72a3,
+                0x6e14116da0605617,
+                0x0c19139cb84c680a,
+            ];
+            let mut a1 = pow(self, &u);
+            let mut alpha = a1;
+
+            alpha.square_assign();
+            alpha.mul_assign(self);
+            let mut a0 = alpha;
+            a0.frobenius_map(1);
+            a0.mul_assign(&alpha);
+
+            let neg1 = Fq2 {
+                c0: NEGATIVE_ONE,
+                c1: Fq::zero(),
+            };
+
+            if a0 == neg1 {
+                CtOption::new(a0, Choice::from(0))
+            } else {
+                a1.mul_assign(self);
+
+                if alpha == neg1 {
+                    a1.mul_assign(&Fq2 {
+                        c0: Fq::zero(),
+                        c1: Fq::one(),
+                    });
+                } else {
+                    alpha += &Fq2::one();
+                    // alpha = alpha^((q - 1) / 2)
+                    // 0x183227397098d014dc2822db40c0ac2ecbc0b548b438e5469e10460b6c3e7ea3
+                    let u: [u64; 4] = [
+                        0x9e10460b6c3e7ea3,
+                        0xcbc0b548b438e546,
+                        0xdc2822db40c0ac2e,
+                        0x183227397098d014,
+                    ];
+                    alpha = pow(&alpha, &u);
+                    a1.mul_assign(&alpha);
+                }
+                CtOption::new(a1, Choice::from(1))
+            }
+        }
     }
 
     pub fn mul_assign(&mut self, other: &Self) {
@@ -334,59 +392,7 @@ impl Field for Fq2 {
     }
 
     fn sqrt(&self) -> CtOption<Self> {
-        // Algorithm 9, https://eprint.iacr.org/2012/685.pdf
-
-        if self.is_zero().into() {
-            CtOption::new(Self::zero(), Choice::from(1))
-        } else {
-            // a1 = self^((q - 3) / 4)
-            // 0xc19139cb84c680a6e14116da060561765e05aa45a1c72a34f082305b61f3f51
-            let u: [u64; 4] = [
-                0x4f082305b61f3f51,
-                0x65e05aa45a1c72a3,
-                0x6e14116da0605617,
-                0x0c19139cb84c680a,
-            ];
-            let mut a1 = self.pow(&u);
-            let mut alpha = a1;
-
-            alpha.square_assign();
-            alpha.mul_assign(self);
-            let mut a0 = alpha;
-            a0.frobenius_map(1);
-            a0.mul_assign(&alpha);
-
-            let neg1 = Fq2 {
-                c0: NEGATIVE_ONE,
-                c1: Fq::zero(),
-            };
-
-            if a0 == neg1 {
-                CtOption::new(a0, Choice::from(0))
-            } else {
-                a1.mul_assign(self);
-
-                if alpha == neg1 {
-                    a1.mul_assign(&Fq2 {
-                        c0: Fq::zero(),
-                        c1: Fq::one(),
-                    });
-                } else {
-                    alpha += &Fq2::one();
-                    // alpha = alpha^((q - 1) / 2)
-                    // 0x183227397098d014dc2822db40c0ac2ecbc0b548b438e5469e10460b6c3e7ea3
-                    let u: [u64; 4] = [
-                        0x9e10460b6c3e7ea3,
-                        0xcbc0b548b438e546,
-                        0xdc2822db40c0ac2e,
-                        0x183227397098d014,
-                    ];
-                    alpha = alpha.pow(&u);
-                    a1.mul_assign(&alpha);
-                }
-                CtOption::new(a1, Choice::from(1))
-            }
-        }
+        self.sqrt_with(<Fq2 as BaseExt>::pow)
     }
 
     fn invert(&self) -> CtOption<Self> {
@@ -420,6 +426,32 @@ impl BaseExt for Fq2 {
     }
 }
 
+impl Legendre for Fq2 {
+    /// An element of `Fq2` is a square if and only if its norm is a square
+    /// in `Fq`.
+    fn jacobi(&self) -> i64 {
+        self.norm().jacobi()
+    }
+}
+
+impl HashToField for Fq2 {
+    const UNIFORM_BYTES: usize = 96;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), Self::UNIFORM_BYTES);
+        Fq2 {
+            c0: Fq::from_uniform_bytes(&bytes[..48]),
+            c1: Fq::from_uniform_bytes(&bytes[48..]),
+        }
+    }
+
+    /// The sign of the first non-zero coefficient, as in Section 4.1 of
+    /// RFC 9380.
+    fn sgn0(&self) -> Choice {
+        self.c0.sgn0() | (self.c0.ct_is_zero() & self.c1.sgn0())
+    }
+}
+
 pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
     // Fq(-1)**(((q^0) - 1) / 2)
     // it's 1 in Montgommery form
@@ -438,6 +470,8 @@ pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
     ]),
 ];
 
+#[cfg(test)]
+use super::LegendreSymbol;
 #[cfg(test)]
 use rand::SeedableRng;
 #[cfg(test)]
@@ -616,6 +650,27 @@ pub fn test_sqrt() {
     }
 }
 
+#[test]
+fn test_sqrt_vartime() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let a = Fq2::random(&mut rng);
+        let sqrt = a.sqrt();
+        let sqrt_vartime = a.sqrt_vartime();
+        assert_eq!(
+            bool::from(sqrt.is_some()),
+            bool::from(sqrt_vartime.is_some())
+        );
+        if bool::from(sqrt.is_some()) {
+            assert_eq!(sqrt.unwrap(), sqrt_vartime.unwrap());
+        }
+    }
+}
+
 #[test]
 fn test_frobenius() {
     let mut rng = XorShiftRng::from_seed([
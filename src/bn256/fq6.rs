@@ -1,5 +1,6 @@
 use super::fq::Fq;
 use super::fq2::Fq2;
+use core::convert::TryInto;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
 use rand::RngCore;
@@ -77,6 +78,36 @@ impl_binops_additive!(Fq6, Fq6);
 impl_binops_multiplicative!(Fq6, Fq6);
 
 impl Fq6 {
+    pub const fn size() -> usize {
+        192
+    }
+
+    /// Attempts to convert a little-endian byte representation of
+    /// an element into a `Fq6`, failing if any coefficient is not canonical.
+    pub fn from_bytes(bytes: &[u8; 192]) -> CtOption<Fq6> {
+        let c0 = Fq2::from_bytes(bytes[0..64].try_into().unwrap());
+        let c1 = Fq2::from_bytes(bytes[64..128].try_into().unwrap());
+        let c2 = Fq2::from_bytes(bytes[128..192].try_into().unwrap());
+        CtOption::new(
+            Fq6 {
+                c0: c0.unwrap_or(Fq2::zero()),
+                c1: c1.unwrap_or(Fq2::zero()),
+                c2: c2.unwrap_or(Fq2::zero()),
+            },
+            c0.is_some() & c1.is_some() & c2.is_some(),
+        )
+    }
+
+    /// Converts an element of `Fq6` into a byte representation in
+    /// little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 192] {
+        let mut res = [0u8; 192];
+        res[0..64].copy_from_slice(&self.c0.to_bytes()[..]);
+        res[64..128].copy_from_slice(&self.c1.to_bytes()[..]);
+        res[128..192].copy_from_slice(&self.c2.to_bytes()[..]);
+        res
+    }
+
     pub fn mul_assign(&mut self, other: &Self) {
         let mut a_a = self.c0;
         let mut b_b = self.c1;
@@ -397,7 +428,7 @@ impl Field for Fq6 {
     }
 
     fn is_zero(&self) -> Choice {
-        self.c0.is_zero() & self.c1.is_zero()
+        self.c0.is_zero() & self.c1.is_zero() & self.c2.is_zero()
     }
 
     fn square(&self) -> Self {
@@ -409,7 +440,33 @@ impl Field for Fq6 {
     }
 
     fn sqrt(&self) -> CtOption<Self> {
-        unimplemented!()
+        // Fq6 is a cubic extension of Fq2, so with Q = q^2 the element
+        // s = a^((Q^2 + Q) / 2) satisfies s^2 = N(a) / a, where
+        // N(a) = a^(1 + Q + Q^2) is the norm of a to Fq2. Hence a is a square
+        // iff N(a) is one, and then sqrt(a) = sqrt(N(a)) / s.
+        if self.is_zero().into() {
+            return CtOption::new(Self::zero(), Choice::from(1));
+        }
+
+        let mut s = self.pow_vartime(Q_SQUARED_PLUS_ONE_OVER_TWO);
+        s.frobenius_map(2);
+
+        let mut a_q = *self;
+        a_q.frobenius_map(2);
+        let mut a_q2 = a_q;
+        a_q2.frobenius_map(2);
+        let norm = (*self * a_q * a_q2).c0;
+
+        norm.sqrt().and_then(|norm_sqrt| {
+            s.invert().map(|s_inv| {
+                s_inv
+                    * Fq6 {
+                        c0: norm_sqrt,
+                        c1: Fq2::zero(),
+                        c2: Fq2::zero(),
+                    }
+            })
+        })
     }
 
     fn invert(&self) -> CtOption<Self> {
@@ -417,6 +474,18 @@ impl Field for Fq6 {
     }
 }
 
+// (q^2 + 1) / 2
+const Q_SQUARED_PLUS_ONE_OVER_TWO: [u64; 8] = [
+    0x9daa2c5113aeb4d9,
+    0x5301039684f56080,
+    0x25280c4e36cb656e,
+    0x82344f4abd092164,
+    0x1376fd2e1a6359c6,
+    0x5805c2a88b1bab03,
+    0x2ccd37be01a4690e,
+    0x0492e25c3b1e5fce,
+];
+
 pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
     // Fq2(u + 9)**(((q^0) - 1) / 3)
     Fq2 {
@@ -693,7 +762,66 @@ fn test_frobenius() {
     }
 }
 
+#[test]
+fn test_is_zero() {
+    assert!(bool::from(Fq6::zero().is_zero()));
+
+    // Each coefficient alone makes the element nonzero
+    for a in [
+        Fq6 {
+            c0: Fq2::one(),
+            ..Fq6::zero()
+        },
+        Fq6 {
+            c1: Fq2::one(),
+            ..Fq6::zero()
+        },
+        Fq6 {
+            c2: Fq2::one(),
+            ..Fq6::zero()
+        },
+    ] {
+        assert!(!bool::from(a.is_zero()));
+    }
+}
+
 #[test]
 fn test_field() {
     crate::tests::field::random_field_tests::<Fq6>("fq6".to_string());
 }
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fq6::zero().sqrt().unwrap(), Fq6::zero());
+    let v2 = Fq6 {
+        c0: Fq2::zero(),
+        c1: Fq2::zero(),
+        c2: Fq2::one(),
+    };
+    assert_eq!(v2.sqrt().unwrap().square(), v2);
+
+    // v is not a square, as its norm is the quadratic nonresidue 9 + u
+    let mut v = Fq6::one();
+    v.mul_by_nonresidue();
+    assert!(bool::from(v.sqrt().is_none()));
+
+    for _ in 0..100 {
+        let a = Fq6::random(&mut rng);
+        let b = a.square();
+        let c = b.sqrt().unwrap();
+        assert!(c == a || c == -a);
+        assert!(bool::from((b * v).sqrt().is_none()));
+
+        let d = Fq6::random(&mut rng);
+        if let Some(e) = Option::<Fq6>::from(d.sqrt()) {
+            assert_eq!(e.square(), d);
+        } else {
+            assert!(bool::from((d * v).sqrt().is_some()));
+        }
+    }
+}
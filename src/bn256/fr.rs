@@ -1,8 +1,7 @@
 #[cfg(all(feature = "asm", target_arch = "x86_64"))]
 use super::assembly::assembly_field;
 use super::common::common_field;
-use super::LegendreSymbol;
-use crate::arithmetic::{adc, mac, sbb, BaseExt, FieldExt, Group};
+use crate::arithmetic::{adc, mac, safegcd_invert, sbb, BaseExt, FieldExt, Group, Legendre};
 use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
@@ -64,6 +63,15 @@ const ROOT_OF_UNITY: Fr = Fr::from_raw([
     0x03ddb9f5166d18b7,
 ]);
 
+/// (t - 1) / 2, where r - 1 = 2^S * t with t odd
+/// 0x183227397098d014dc2822db40c0ac2e9419f4243cdcb848a1f0fac9f
+const T_MINUS1_OVER2: [u64; 4] = [
+    0xcdcb848a1f0fac9f,
+    0x0c0ac2e9419f4243,
+    0x098d014dc2822db4,
+    0x0000000183227397,
+];
+
 const BASEEXT_MODULUS: &'static str =
     "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
 
@@ -110,8 +118,43 @@ common_field!(
 );
 
 impl Fr {
-    pub fn legendre(&self) -> LegendreSymbol {
-        unimplemented!()
+    /// Computes the square root of this element in variable time with the
+    /// Tonelli-Shanks algorithm. This must only be used on public data.
+    pub fn sqrt_vartime(&self) -> CtOption<Self> {
+        if bool::from(self.ct_is_zero()) {
+            return CtOption::new(Self::zero(), Choice::from(1));
+        }
+
+        // x = self^((t + 1) / 2) and b = self^t, where r - 1 = 2^S * t
+        let w = self.pow_vartime_sliding(&T_MINUS1_OVER2);
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+        let mut v = S;
+
+        while b != Self::one() {
+            // Find the least k such that b^(2^k) = 1, with k < v if self is a square
+            let mut k = 0;
+            let mut b2k = b;
+            while b2k != Self::one() && k < v {
+                b2k = b2k.square();
+                k += 1;
+            }
+            if k == v {
+                return CtOption::new(Self::zero(), Choice::from(0));
+            }
+
+            let mut w = z;
+            for _ in 1..(v - k) {
+                w = w.square();
+            }
+            z = w.square();
+            b *= z;
+            x *= w;
+            v = k;
+        }
+
+        CtOption::new(x, Choice::from(1))
     }
 }
 
@@ -153,22 +196,47 @@ impl ff::Field for Fr {
         self.square()
     }
 
-    /// Computes the square root of this element, if it exists.
+    /// Computes the square root of this element, if it exists, with a
+    /// constant-time variant of the Tonelli-Shanks algorithm.
     fn sqrt(&self) -> CtOption<Self> {
-        unimplemented!()
+        // x = self^((t + 1) / 2) and b = self^t, where r - 1 = 2^S * t
+        let w = self.pow(&T_MINUS1_OVER2);
+        let mut x = self * w;
+        let mut b = x * w;
+        let mut z = ROOT_OF_UNITY;
+        let mut v = S;
+
+        // Each round runs for every possible order of b, so that the number
+        // of operations does not depend on the input
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v: Choice = 1.into();
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Self::one());
+                let squared = Self::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Self::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Self::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Self::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Self::conditional_select(&result, &x, b.ct_eq(&Self::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(self))
     }
 
     /// Computes the multiplicative inverse of this element,
     /// failing if the element is zero.
     fn invert(&self) -> CtOption<Self> {
-        let tmp = self.pow(&[
-            0x43e1f593efffffff,
-            0x2833e84879b97091,
-            0xb85045b68181585d,
-            0x30644e72e131a029,
-        ]);
-
-        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+        self.invert_safegcd()
     }
 }
 
@@ -258,8 +326,14 @@ impl ec_gpu::GpuField for Fr {
     }
 }
 
+#[cfg(test)]
+use super::LegendreSymbol;
 #[cfg(test)]
 use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
 
 #[test]
 fn test_zeta() {
@@ -316,3 +390,87 @@ fn test_from_u512() {
 fn test_field() {
     crate::tests::field::random_field_tests::<Fr>("fr".to_string());
 }
+
+#[test]
+fn test_legendre() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fr::zero().legendre(), LegendreSymbol::Zero);
+    assert_eq!(Fr::one().legendre(), LegendreSymbol::QuadraticResidue);
+    assert_eq!(GENERATOR.legendre(), LegendreSymbol::QuadraticNonResidue);
+    assert_eq!((-Fr::one()).legendre(), LegendreSymbol::QuadraticResidue);
+
+    for _ in 0..1000 {
+        let a = Fr::random(&mut rng);
+        // Euler's criterion, a^((r - 1) / 2)
+        let euler = a.pow_vartime(&[
+            0xa1f0fac9f8000000,
+            0x9419f4243cdcb848,
+            0xdc2822db40c0ac2e,
+            0x183227397098d014,
+        ]);
+        let expected = if euler == Fr::one() { 1 } else { -1 };
+        assert_eq!(a.jacobi(), expected);
+        assert_eq!(bool::from(a.ct_quadratic_residue()), expected == 1);
+        assert_eq!(bool::from(a.ct_quadratic_non_residue()), expected == -1);
+        assert_eq!(a.square().legendre(), LegendreSymbol::QuadraticResidue);
+        assert_eq!(
+            (a.square() * GENERATOR).legendre(),
+            LegendreSymbol::QuadraticNonResidue
+        );
+    }
+}
+
+#[test]
+fn test_sqrt_vartime() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fr::zero().sqrt_vartime().unwrap(), Fr::zero());
+
+    for _ in 0..1000 {
+        let a = Fr::random(&mut rng);
+        let b = a.square();
+        let c = b.sqrt_vartime().unwrap();
+        assert!(c == a || c == -a);
+
+        let d = a.sqrt_vartime();
+        assert_eq!(bool::from(d.is_some()), a.jacobi() == 1);
+        if bool::from(d.is_some()) {
+            assert_eq!(d.unwrap().square(), a);
+        }
+    }
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+    assert!(bool::from(GENERATOR.sqrt().is_none()));
+
+    for _ in 0..1000 {
+        let a = Fr::random(&mut rng);
+        let b = a.square();
+        let c = b.sqrt().unwrap();
+        assert!(c == a || c == -a);
+
+        let d = a.sqrt();
+        assert_eq!(
+            bool::from(d.is_some()),
+            a.legendre() == LegendreSymbol::QuadraticResidue
+        );
+        if bool::from(d.is_some()) {
+            assert_eq!(d.unwrap().square(), a);
+            assert_eq!(d.unwrap(), a.sqrt_vartime().unwrap());
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use crate::arithmetic::mul_512;
-use crate::arithmetic::{BaseExt, Coordinates, CurveAffine, CurveExt, FieldExt, Group};
+use crate::arithmetic::{
+    BaseExt, Coordinates, CurveAffine, CurveExt, CurveHasher, FieldExt, Group, HashToCurve,
+};
 use crate::bn256::Fq;
 use crate::bn256::Fq2;
 use crate::bn256::Fr;
+use crate::hash_to_curve::Svdw;
 use core::cmp;
 use core::fmt::Debug;
 use core::iter::Sum;
@@ -12,6 +15,7 @@ use group::{
     cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve as _, Group as _, GroupEncoding,
 };
 use rand::RngCore;
+use std::sync::OnceLock;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 new_curve_impl!(
@@ -24,7 +28,8 @@ new_curve_impl!(
     (G1_GENERATOR_X,G1_GENERATOR_Y),
     G1_B,
     ENDO_G1_CUBE_ROOT,
-    "bn256_g1"
+    "bn256_g1",
+    "BN254G1_XMD:SHA-256_SVDW_RO_"
 );
 
 new_curve_impl!(
@@ -37,7 +42,8 @@ new_curve_impl!(
     (G2_GENERATOR_X, G2_GENERATOR_Y),
     G2_B,
     ENDO_G2_CUBE_ROOT,
-    "bn256_g2"
+    "bn256_g2",
+    "BN254G2_XMD:SHA-256_SVDW-PAIRING-BN256_RO_"
 );
 
 #[cfg(feature = "gpu")]
@@ -207,6 +213,7 @@ mod tests {
     use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine};
     use rand::SeedableRng;
     use rand_xorshift::XorShiftRng;
+    use subtle::CtOption;
 
     fn is_on_curve<G: CurveExt>() {
         assert!(bool::from(G::identity().is_on_curve()));
@@ -449,6 +456,22 @@ mod tests {
         assert_eq!(t0, t1);
     }
 
+    fn from_bytes_vartime<G: CurveExt>(decode: impl Fn(&G::Repr) -> CtOption<G>) {
+        assert!(bool::from(
+            decode(&G::identity().to_bytes()).unwrap().is_identity()
+        ));
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let bytes = G::random(&mut rng).to_bytes();
+            assert_eq!(decode(&bytes).unwrap(), G::from_bytes(&bytes).unwrap());
+        }
+    }
+
     #[test]
     fn test_cofactor() {
         let mut rng = XorShiftRng::from_seed([
@@ -479,6 +502,8 @@ mod tests {
         mixed_addition::<G2>();
         multiplication::<G2>();
         batch_normalize::<G1>();
+        from_bytes_vartime::<G1>(G1::from_bytes_vartime);
+        from_bytes_vartime::<G2>(G2::from_bytes_vartime);
     }
 }
 
@@ -18,9 +18,4 @@ pub use fq6::*;
 pub use fr::*;
 pub use g::*;
 
-#[derive(Debug, PartialEq)]
-pub enum LegendreSymbol {
-    Zero = 0,
-    QuadraticResidue = 1,
-    QuadraticNonResidue = -1,
-}
+pub use crate::arithmetic::{Legendre, LegendreSymbol};
@@ -8,7 +8,8 @@ macro_rules! new_curve_impl {
     $generator:expr,
     $constant_b:expr,
     $cube_root:expr,
-    $curve_id:literal
+    $curve_id:literal,
+    $suite_id:literal
     ) => {
 
         #[derive(Copy, Clone, Debug)]
@@ -41,6 +42,12 @@ macro_rules! new_curve_impl {
             const fn curve_constant_b() -> $base {
                 $name_affine::curve_constant_b()
             }
+
+            /// Decodes a compressed point in variable time, see
+            /// `from_bytes_vartime` on the affine form.
+            pub fn from_bytes_vartime(bytes: &$name_compressed) -> CtOption<Self> {
+                $name_affine::from_bytes_vartime(bytes).map(Self::from)
+            }
         }
 
         impl $name_affine {
@@ -79,6 +86,42 @@ macro_rules! new_curve_impl {
                 }
             }
 
+            /// Decodes a compressed point in variable time, using the variable-time
+            /// square root of the base field. This must only be used on public
+            /// inputs, such as the elements of a proof.
+            pub fn from_bytes_vartime(bytes: &$name_compressed) -> CtOption<Self> {
+                Self::from_bytes_with(bytes, |v| v.sqrt_vartime())
+            }
+
+            fn from_bytes_with(
+                bytes: &$name_compressed,
+                sqrt: impl Fn(&$base) -> CtOption<$base>,
+            ) -> CtOption<Self> {
+                let bytes = &bytes.0;
+                let mut tmp = *bytes;
+                let ysign = Choice::from(tmp[$base::size() - 1] >> 7);
+                tmp[$base::size() - 1] &= 0b0111_1111;
+
+                $base::from_bytes(&tmp).and_then(|x| {
+                    CtOption::new(Self::identity(), x.ct_is_zero() & (!ysign)).or_else(|| {
+                        let x3 = x.square() * x;
+                        sqrt(&(x3 + $name::curve_constant_b())).and_then(|y| {
+                            let sign = Choice::from(y.to_bytes()[0] & 1);
+
+                            let y = $base::conditional_select(&y, &-y, ysign ^ sign);
+
+                            CtOption::new(
+                                $name_affine {
+                                    x,
+                                    y,
+                                },
+                                Choice::from(1u8),
+                            )
+                        })
+                    })
+                })
+            }
+
         }
 
         // Compressed
@@ -201,6 +244,27 @@ macro_rules! new_curve_impl {
             }
         }
 
+        impl $name {
+            fn svdw() -> &'static Svdw<$base> {
+                // The constants of the map only depend on the curve
+                static MAP: OnceLock<Svdw<$base>> = OnceLock::new();
+                MAP.get_or_init(|| Svdw::new($name::curve_constant_b()))
+            }
+        }
+
+        impl HashToCurve for $name {
+            const SUITE_ID: &'static str = $suite_id;
+
+            fn hash_to_curve(domain_prefix: &str) -> CurveHasher<'_, Self> {
+                let dst = format!("{}-{}_XMD:SHA-256_SVDW_RO_", domain_prefix, Self::CURVE_ID);
+                crate::hash_to_curve::hash_to_curve(dst.into_bytes(), $name::svdw())
+            }
+
+            fn hash_to_curve_with_dst(dst: &[u8]) -> CurveHasher<'_, Self> {
+                crate::hash_to_curve::hash_to_curve(dst.to_vec(), $name::svdw())
+            }
+        }
+
         impl group::Curve for $name {
 
             type AffineRepr = $name_affine;
@@ -413,29 +477,7 @@ macro_rules! new_curve_impl {
             type Repr = $name_compressed;
 
             fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
-                let bytes = &bytes.0;
-                let mut tmp = *bytes;
-                let ysign = Choice::from(tmp[$base::size() - 1] >> 7);
-                tmp[$base::size() - 1] &= 0b0111_1111;
-
-                $base::from_bytes(&tmp).and_then(|x| {
-                    CtOption::new(Self::identity(), x.ct_is_zero() & (!ysign)).or_else(|| {
-                        let x3 = x.square() * x;
-                        (x3 + $name::curve_constant_b()).sqrt().and_then(|y| {
-                            let sign = Choice::from(y.to_bytes()[0] & 1);
-
-                            let y = $base::conditional_select(&y, &-y, ysign ^ sign);
-
-                            CtOption::new(
-                                $name_affine {
-                                    x,
-                                    y,
-                                },
-                                Choice::from(1u8),
-                            )
-                        })
-                    })
-                })
+                Self::from_bytes_with(bytes, |v| v.sqrt())
             }
 
             fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
@@ -0,0 +1,733 @@
+//! This module implements the verifier of the Groth16 proving system,
+//! https://eprint.iacr.org/2016/260.pdf
+//!
+//! A proof $(A, B, C)$ for the public inputs $x_i$ is accepted when
+//! $$e(A, B) = e(\alpha, \beta) \cdot e(\sum_i x_i IC_i, \gamma) \cdot e(C, \delta),$$
+//! with $x_0 = 1$. Verifying keys and proofs over BN254 can be imported from
+//! the JSON files of snarkjs and from the binary encoding of gnark.
+
+use crate::arithmetic::{best_multiexp, CurveAffine, Engine, MillerLoopResult, MultiMillerLoop};
+use crate::bn256::{Bn256, Fq, Fq2, Fr, G1Affine, G2Affine, G2};
+use ff::{Field, PrimeField};
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve, Group};
+use num_bigint::BigUint;
+use rand_core::RngCore;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::io;
+use subtle::{Choice, ConstantTimeEq};
+
+/// The verifying key of a circuit.
+#[derive(Clone, Debug)]
+pub struct VerifyingKey<E: Engine> {
+    /// $[\alpha] G_1$.
+    pub alpha_g1: E::G1Affine,
+    /// $[\beta] G_2$.
+    pub beta_g2: E::G2Affine,
+    /// $[\gamma] G_2$.
+    pub gamma_g2: E::G2Affine,
+    /// $[\delta] G_2$.
+    pub delta_g2: E::G2Affine,
+    /// The bases $IC_i$ of the public inputs, starting with the one of the
+    /// constant $1$.
+    pub ic: Vec<E::G1Affine>,
+}
+
+/// A verifying key with the pairing $e(\alpha, \beta)$ precomputed and the
+/// negated G2 points prepared for the Miller loop.
+#[derive(Clone)]
+pub struct PreparedVerifyingKey<E: MultiMillerLoop> {
+    alpha_g1_beta_g2: E::Gt,
+    neg_gamma_g2: E::G2Prepared,
+    neg_delta_g2: E::G2Prepared,
+    ic: Vec<E::G1Affine>,
+}
+
+/// A proof.
+#[derive(Clone, Debug)]
+pub struct Proof<E: Engine> {
+    /// $A \in G_1$.
+    pub a: E::G1Affine,
+    /// $B \in G_2$.
+    pub b: E::G2Affine,
+    /// $C \in G_1$.
+    pub c: E::G1Affine,
+}
+
+impl<E: MultiMillerLoop> From<&VerifyingKey<E>> for PreparedVerifyingKey<E> {
+    fn from(vk: &VerifyingKey<E>) -> Self {
+        PreparedVerifyingKey {
+            alpha_g1_beta_g2: E::pairing(&vk.alpha_g1, &vk.beta_g2),
+            neg_gamma_g2: E::G2Prepared::from(-vk.gamma_g2),
+            neg_delta_g2: E::G2Prepared::from(-vk.delta_g2),
+            ic: vk.ic.clone(),
+        }
+    }
+}
+
+impl<E: MultiMillerLoop> PreparedVerifyingKey<E> {
+    /// Returns the number of public inputs, or zero if the key has no bases
+    /// at all.
+    pub fn num_inputs(&self) -> usize {
+        self.ic.len().saturating_sub(1)
+    }
+}
+
+/// Checks a proof against the public inputs, which do not include the
+/// constant $1$, with a single Miller loop and final exponentiation.
+/// Proofs are rejected for a key without any base $IC_0$.
+pub fn verify_proof<E: MultiMillerLoop>(
+    pvk: &PreparedVerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: &[E::Scalar],
+) -> Choice {
+    if pvk.ic.is_empty() || public_inputs.len() != pvk.num_inputs() {
+        return Choice::from(0);
+    }
+    let acc = (pvk.ic[0].to_curve() + best_multiexp(public_inputs, &pvk.ic[1..])).to_affine();
+    let b = E::G2Prepared::from(proof.b);
+
+    E::multi_miller_loop(&[
+        (&proof.a, &b),
+        (&acc, &pvk.neg_gamma_g2),
+        (&proof.c, &pvk.neg_delta_g2),
+    ])
+    .final_exponentiation()
+    .ct_eq(&pvk.alpha_g1_beta_g2)
+}
+
+/// Checks many proofs for the same verifying key at once.
+///
+/// The verification equation of each proof is raised to a random power
+/// sampled from `rng`, so that the inputs and $C$ of all proofs are folded
+/// into two multi-scalar multiplications, and a single Miller loop with one
+/// term per proof plus two is run. A batch containing an invalid proof is
+/// accepted with probability at most $1/|F_r|$.
+pub fn batch_verify<E: MultiMillerLoop>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[(&Proof<E>, &[E::Scalar])],
+    mut rng: impl RngCore,
+) -> Choice {
+    if pvk.ic.is_empty() {
+        return Choice::from(0);
+    }
+    let mut ic_scalars = vec![E::Scalar::zero(); pvk.ic.len()];
+    let mut c = E::G1::identity();
+    let mut challenges = E::Scalar::zero();
+    let mut a = vec![];
+    let mut b = vec![];
+    for (proof, public_inputs) in proofs {
+        if public_inputs.len() != pvk.num_inputs() {
+            return Choice::from(0);
+        }
+        let r = E::Scalar::random(&mut rng);
+
+        ic_scalars[0] += r;
+        for (scalar, input) in ic_scalars[1..].iter_mut().zip(public_inputs.iter()) {
+            *scalar += r * input;
+        }
+        c += proof.c * r;
+        challenges += r;
+        a.push(proof.a * r);
+        b.push(E::G2Prepared::from(proof.b));
+    }
+
+    let acc = best_multiexp(&ic_scalars, &pvk.ic).to_affine();
+    let c = c.to_affine();
+    let mut a_affine = vec![E::G1Affine::identity(); a.len()];
+    E::G1::batch_normalize(&a, &mut a_affine);
+
+    let mut terms: Vec<_> = a_affine.iter().zip(b.iter()).collect();
+    terms.push((&acc, &pvk.neg_gamma_g2));
+    terms.push((&c, &pvk.neg_delta_g2));
+
+    E::multi_miller_loop(&terms)
+        .final_exponentiation()
+        .ct_eq(&(pvk.alpha_g1_beta_g2 * challenges))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl VerifyingKey<Bn256> {
+    /// Parses a `verification_key.json` file of snarkjs for the `bn128`
+    /// curve.
+    pub fn from_snarkjs_json(json: &str) -> io::Result<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        check_snarkjs_header(&value)?;
+
+        let ic = snarkjs_array(snarkjs_field(&value, "IC")?, None)?
+            .iter()
+            .map(snarkjs_g1)
+            .collect::<io::Result<Vec<_>>>()?;
+        if ic.is_empty() {
+            return Err(invalid_data("missing IC in snarkjs json"));
+        }
+        if let Some(n) = value.get("nPublic").and_then(Value::as_u64) {
+            if n as usize + 1 != ic.len() {
+                return Err(invalid_data("nPublic does not match IC in snarkjs json"));
+            }
+        }
+
+        Ok(VerifyingKey {
+            alpha_g1: snarkjs_g1(snarkjs_field(&value, "vk_alpha_1")?)?,
+            beta_g2: snarkjs_g2(snarkjs_field(&value, "vk_beta_2")?)?,
+            gamma_g2: snarkjs_g2(snarkjs_field(&value, "vk_gamma_2")?)?,
+            delta_g2: snarkjs_g2(snarkjs_field(&value, "vk_delta_2")?)?,
+            ic,
+        })
+    }
+
+    /// Reads a verifying key written by gnark's `WriteTo` or `WriteRawTo`
+    /// for BN254. Only the fields up to the bases of the public inputs are
+    /// read, so keys of circuits with commitments are not supported.
+    pub fn read_gnark<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let alpha_g1 = read_gnark_g1(reader)?;
+        let _beta_g1 = read_gnark_g1(reader)?;
+        let beta_g2 = read_gnark_g2(reader)?;
+        let gamma_g2 = read_gnark_g2(reader)?;
+        let _delta_g1 = read_gnark_g1(reader)?;
+        let delta_g2 = read_gnark_g2(reader)?;
+
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len);
+        if len == 0 {
+            return Err(invalid_data("missing bases of the public inputs"));
+        }
+        let ic = (0..len)
+            .map(|_| read_gnark_g1(reader))
+            .collect::<io::Result<_>>()?;
+
+        Ok(VerifyingKey {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            ic,
+        })
+    }
+}
+
+impl Proof<Bn256> {
+    /// Parses a `proof.json` file of snarkjs for the `bn128` curve.
+    pub fn from_snarkjs_json(json: &str) -> io::Result<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        check_snarkjs_header(&value)?;
+
+        Ok(Proof {
+            a: snarkjs_g1(snarkjs_field(&value, "pi_a")?)?,
+            b: snarkjs_g2(snarkjs_field(&value, "pi_b")?)?,
+            c: snarkjs_g1(snarkjs_field(&value, "pi_c")?)?,
+        })
+    }
+
+    /// Reads a proof written by gnark's `WriteTo` or `WriteRawTo` for BN254.
+    /// Commitments that follow the proof are not read.
+    pub fn read_gnark<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Proof {
+            a: read_gnark_g1(reader)?,
+            b: read_gnark_g2(reader)?,
+            c: read_gnark_g1(reader)?,
+        })
+    }
+}
+
+/// Parses a `public.json` file of snarkjs, a list of decimal strings.
+pub fn public_inputs_from_snarkjs_json(json: &str) -> io::Result<Vec<Fr>> {
+    let value: Value = serde_json::from_str(json)?;
+    snarkjs_array(&value, None)?
+        .iter()
+        .map(|x| {
+            Option::from(Fr::from_repr(decimal_bytes(x)?))
+                .ok_or_else(|| invalid_data("public input is not reduced"))
+        })
+        .collect()
+}
+
+fn check_snarkjs_header(value: &Value) -> io::Result<()> {
+    if let Some(protocol) = value.get("protocol") {
+        if *protocol != "groth16" {
+            return Err(invalid_data("not a groth16 snarkjs json"));
+        }
+    }
+    if let Some(curve) = value.get("curve") {
+        if *curve != "bn128" {
+            return Err(invalid_data("snarkjs json is not over bn128"));
+        }
+    }
+    Ok(())
+}
+
+fn snarkjs_field<'a>(value: &'a Value, key: &str) -> io::Result<&'a Value> {
+    value
+        .get(key)
+        .ok_or_else(|| invalid_data(&format!("missing {} in snarkjs json", key)))
+}
+
+fn snarkjs_array(value: &Value, len: Option<usize>) -> io::Result<&[Value]> {
+    match value.as_array() {
+        Some(array) if len.map_or(true, |len| array.len() == len) => Ok(array.as_slice()),
+        _ => Err(invalid_data("unexpected array in snarkjs json")),
+    }
+}
+
+/// Parses a decimal string into its little endian bytes.
+fn decimal_bytes(value: &Value) -> io::Result<[u8; 32]> {
+    let n = value
+        .as_str()
+        .and_then(|s| BigUint::parse_bytes(s.as_bytes(), 10))
+        .ok_or_else(|| invalid_data("expected a decimal string in snarkjs json"))?;
+    let le = n.to_bytes_le();
+    if le.len() > 32 {
+        return Err(invalid_data("integer is too large in snarkjs json"));
+    }
+    let mut bytes = [0u8; 32];
+    bytes[..le.len()].copy_from_slice(&le);
+    Ok(bytes)
+}
+
+fn snarkjs_fq(value: &Value) -> io::Result<Fq> {
+    Option::from(Fq::from_repr(decimal_bytes(value)?))
+        .ok_or_else(|| invalid_data("coordinate is not reduced"))
+}
+
+fn snarkjs_fq2(value: &Value) -> io::Result<Fq2> {
+    let c = snarkjs_array(value, Some(2))?;
+    Ok(Fq2 {
+        c0: snarkjs_fq(&c[0])?,
+        c1: snarkjs_fq(&c[1])?,
+    })
+}
+
+/// Parses a point in the projective form of snarkjs, which is normalized to
+/// $Z = 1$, or $Z = 0$ for the identity.
+fn snarkjs_g1(value: &Value) -> io::Result<G1Affine> {
+    let c = snarkjs_array(value, Some(3))?;
+    let (x, y, z) = (snarkjs_fq(&c[0])?, snarkjs_fq(&c[1])?, snarkjs_fq(&c[2])?);
+    if z == Fq::zero() {
+        Ok(G1Affine::identity())
+    } else if z == Fq::one() {
+        Option::from(G1Affine::from_xy(x, y))
+            .ok_or_else(|| invalid_data("point is not on the curve"))
+    } else {
+        Err(invalid_data("point is not normalized in snarkjs json"))
+    }
+}
+
+fn snarkjs_g2(value: &Value) -> io::Result<G2Affine> {
+    let c = snarkjs_array(value, Some(3))?;
+    let (x, y, z) = (
+        snarkjs_fq2(&c[0])?,
+        snarkjs_fq2(&c[1])?,
+        snarkjs_fq2(&c[2])?,
+    );
+    if z == Fq2::zero() {
+        Ok(G2Affine::identity())
+    } else if z == Fq2::one() {
+        g2_from_xy(x, y)
+    } else {
+        Err(invalid_data("point is not normalized in snarkjs json"))
+    }
+}
+
+/// Creates a point of G2, checking that it is on the curve and in the prime
+/// order subgroup.
+fn g2_from_xy(x: Fq2, y: Fq2) -> io::Result<G2Affine> {
+    let p: G2Affine = Option::from(G2Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("point is not on the curve"))?;
+    if !bool::from(G2::from(p).is_torsion_free()) {
+        return Err(invalid_data("point is not in the prime order subgroup"));
+    }
+    Ok(p)
+}
+
+// The flags in the two top bits of the first byte of gnark's encodings
+const GNARK_MASK: u8 = 0b11 << 6;
+const GNARK_UNCOMPRESSED: u8 = 0;
+const GNARK_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+const GNARK_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const GNARK_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+
+/// Parses a big endian encoding of an element of `Fq`.
+fn gnark_fq(bytes: &[u8]) -> io::Result<Fq> {
+    let mut repr = [0u8; 32];
+    for (r, b) in repr.iter_mut().zip(bytes.iter().rev()) {
+        *r = *b;
+    }
+    Option::from(Fq::from_repr(repr)).ok_or_else(|| invalid_data("coordinate is not reduced"))
+}
+
+/// Returns whether `x` is larger than $(q - 1) / 2$.
+fn lexicographically_largest(x: &Fq) -> bool {
+    let (a, b) = (x.to_repr(), (-x).to_repr());
+    a.iter().rev().cmp(b.iter().rev()) == Ordering::Greater
+}
+
+/// Returns whether `x` is larger than its negation, comparing the $c_1$
+/// coefficients first like gnark.
+fn lexicographically_largest_fq2(x: &Fq2) -> bool {
+    if x.c1 == Fq::zero() {
+        lexicographically_largest(&x.c0)
+    } else {
+        lexicographically_largest(&x.c1)
+    }
+}
+
+/// Reads the flags of the first byte of a gnark encoding, clearing them,
+/// and the rest of the encoding if it is uncompressed.
+fn read_gnark_point<R: io::Read>(reader: &mut R, size: usize) -> io::Result<(u8, Vec<u8>)> {
+    let mut bytes = vec![0u8; size];
+    reader.read_exact(&mut bytes)?;
+    let flag = bytes[0] & GNARK_MASK;
+    bytes[0] &= !GNARK_MASK;
+
+    match flag {
+        GNARK_UNCOMPRESSED => {
+            bytes.resize(2 * size, 0);
+            reader.read_exact(&mut bytes[size..])?;
+        }
+        GNARK_COMPRESSED_INFINITY => {
+            if bytes.iter().any(|b| *b != 0) {
+                return Err(invalid_data("invalid encoding of the identity"));
+            }
+        }
+        GNARK_COMPRESSED_SMALLEST | GNARK_COMPRESSED_LARGEST => {}
+        _ => return Err(invalid_data("unknown gnark point flags")),
+    }
+    Ok((flag, bytes))
+}
+
+fn read_gnark_g1<R: io::Read>(reader: &mut R) -> io::Result<G1Affine> {
+    let (flag, bytes) = read_gnark_point(reader, 32)?;
+    let x = gnark_fq(&bytes[..32])?;
+    let y = match flag {
+        GNARK_COMPRESSED_INFINITY => return Ok(G1Affine::identity()),
+        GNARK_UNCOMPRESSED => gnark_fq(&bytes[32..])?,
+        _ => {
+            let y: Fq = Option::from((x.square() * x + G1Affine::b()).sqrt())
+                .ok_or_else(|| invalid_data("point is not on the curve"))?;
+            if lexicographically_largest(&y) == (flag == GNARK_COMPRESSED_LARGEST) {
+                y
+            } else {
+                -y
+            }
+        }
+    };
+    Option::from(G1Affine::from_xy(x, y)).ok_or_else(|| invalid_data("point is not on the curve"))
+}
+
+/// Reads a point of G2, whose coordinates are encoded with $c_1$ first.
+fn read_gnark_g2<R: io::Read>(reader: &mut R) -> io::Result<G2Affine> {
+    let (flag, bytes) = read_gnark_point(reader, 64)?;
+    let fq2 = |bytes: &[u8]| -> io::Result<Fq2> {
+        Ok(Fq2 {
+            c0: gnark_fq(&bytes[32..64])?,
+            c1: gnark_fq(&bytes[..32])?,
+        })
+    };
+    let x = fq2(&bytes[..64])?;
+    let y = match flag {
+        GNARK_COMPRESSED_INFINITY => return Ok(G2Affine::identity()),
+        GNARK_UNCOMPRESSED => fq2(&bytes[64..])?,
+        _ => {
+            let y: Fq2 = Option::from((x.square() * x + G2Affine::b()).sqrt())
+                .ok_or_else(|| invalid_data("point is not on the curve"))?;
+            if lexicographically_largest_fq2(&y) == (flag == GNARK_COMPRESSED_LARGEST) {
+                y
+            } else {
+                -y
+            }
+        }
+    };
+    g2_from_xy(x, y)
+}
+
+#[cfg(test)]
+use crate::bn256::G1;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+/// Returns a verifying key with known trapdoors, and a function that forges
+/// proofs with them.
+#[cfg(test)]
+fn forging_setup(
+    num_inputs: usize,
+    mut rng: impl RngCore,
+) -> (VerifyingKey<Bn256>, impl FnMut(&[Fr]) -> Proof<Bn256>) {
+    let (alpha, beta, gamma, delta) = (
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+    );
+    let ic: Vec<_> = (0..=num_inputs).map(|_| Fr::random(&mut rng)).collect();
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+
+    let vk = VerifyingKey {
+        alpha_g1: (g1 * alpha).to_affine(),
+        beta_g2: (g2 * beta).to_affine(),
+        gamma_g2: (g2 * gamma).to_affine(),
+        delta_g2: (g2 * delta).to_affine(),
+        ic: ic.iter().map(|x| (g1 * x).to_affine()).collect(),
+    };
+
+    // ab = alpha beta + (sum_i x_i ic_i) gamma + c delta
+    let forge = move |inputs: &[Fr]| {
+        let acc = inputs
+            .iter()
+            .zip(ic[1..].iter())
+            .fold(ic[0], |acc, (x, y)| acc + *x * y);
+        let a = Fr::random(&mut rng);
+        let b = Fr::random(&mut rng);
+        let c = (a * b - alpha * beta - acc * gamma) * delta.invert().unwrap();
+        Proof {
+            a: (g1 * a).to_affine(),
+            b: (g2 * b).to_affine(),
+            c: (g1 * c).to_affine(),
+        }
+    };
+    (vk, forge)
+}
+
+#[test]
+fn test_verify_proof() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let (vk, mut forge) = forging_setup(3, XorShiftRng::from_seed([1; 16]));
+    let pvk = PreparedVerifyingKey::from(&vk);
+    assert_eq!(pvk.num_inputs(), 3);
+
+    let inputs: Vec<Vec<Fr>> = (0..4)
+        .map(|_| (0..3).map(|_| Fr::random(&mut rng)).collect())
+        .collect();
+    let proofs: Vec<_> = inputs.iter().map(|inputs| forge(&inputs[..])).collect();
+    for (proof, inputs) in proofs.iter().zip(inputs.iter()) {
+        assert!(bool::from(verify_proof(&pvk, proof, inputs)));
+    }
+
+    let mut wrong_inputs = inputs[0].clone();
+    wrong_inputs[1] += Fr::one();
+    assert!(!bool::from(verify_proof(&pvk, &proofs[0], &wrong_inputs)));
+    assert!(!bool::from(verify_proof(&pvk, &proofs[0], &inputs[0][..2])));
+    let mut wrong_proof = proofs[0].clone();
+    wrong_proof.c = (G1::from(wrong_proof.c) + G1::generator()).to_affine();
+    assert!(!bool::from(verify_proof(&pvk, &wrong_proof, &inputs[0])));
+
+    let mut batch: Vec<_> = proofs
+        .iter()
+        .zip(inputs.iter())
+        .map(|(proof, inputs)| (proof, &inputs[..]))
+        .collect();
+    assert!(bool::from(batch_verify(&pvk, &[], &mut rng)));
+    assert!(bool::from(batch_verify(&pvk, &batch, &mut rng)));
+    batch[2] = (&wrong_proof, &inputs[0][..]);
+    assert!(!bool::from(batch_verify(&pvk, &batch, &mut rng)));
+
+    // A key without any base rejects every proof
+    let mut empty = pvk.clone();
+    empty.ic.clear();
+    assert_eq!(empty.num_inputs(), 0);
+    assert!(!bool::from(verify_proof(&empty, &proofs[0], &[])));
+    assert!(!bool::from(batch_verify(&empty, &[], &mut rng)));
+    assert!(!bool::from(batch_verify(
+        &empty,
+        &[(&proofs[0], &[])],
+        &mut rng
+    )));
+}
+
+#[cfg(test)]
+fn decimal(bytes: [u8; 32]) -> String {
+    format!("\"{}\"", BigUint::from_bytes_le(&bytes))
+}
+
+#[cfg(test)]
+fn snarkjs_g1_json(p: &G1Affine) -> String {
+    if bool::from(p.is_identity()) {
+        return "[\"0\", \"1\", \"0\"]".to_string();
+    }
+    format!(
+        "[{}, {}, \"1\"]",
+        decimal(p.x.to_repr()),
+        decimal(p.y.to_repr())
+    )
+}
+
+#[cfg(test)]
+fn snarkjs_g2_json(p: &G2Affine) -> String {
+    format!(
+        "[[{}, {}], [{}, {}], [\"1\", \"0\"]]",
+        decimal(p.x.c0.to_repr()),
+        decimal(p.x.c1.to_repr()),
+        decimal(p.y.c0.to_repr()),
+        decimal(p.y.c1.to_repr())
+    )
+}
+
+#[test]
+fn test_snarkjs_json() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let (vk, mut forge) = forging_setup(2, XorShiftRng::from_seed([2; 16]));
+    let inputs = vec![Fr::from(33), Fr::random(&mut rng)];
+    let proof = forge(&inputs);
+
+    let vk_json = format!(
+        r#"{{
+            "protocol": "groth16",
+            "curve": "bn128",
+            "nPublic": 2,
+            "vk_alpha_1": {},
+            "vk_beta_2": {},
+            "vk_gamma_2": {},
+            "vk_delta_2": {},
+            "IC": [{}, {}, {}]
+        }}"#,
+        snarkjs_g1_json(&vk.alpha_g1),
+        snarkjs_g2_json(&vk.beta_g2),
+        snarkjs_g2_json(&vk.gamma_g2),
+        snarkjs_g2_json(&vk.delta_g2),
+        snarkjs_g1_json(&vk.ic[0]),
+        snarkjs_g1_json(&vk.ic[1]),
+        snarkjs_g1_json(&vk.ic[2]),
+    );
+    let proof_json = format!(
+        r#"{{
+            "pi_a": {},
+            "pi_b": {},
+            "pi_c": {},
+            "protocol": "groth16",
+            "curve": "bn128"
+        }}"#,
+        snarkjs_g1_json(&proof.a),
+        snarkjs_g2_json(&proof.b),
+        snarkjs_g1_json(&proof.c),
+    );
+    let public_json = format!(
+        "[{}, {}]",
+        decimal(inputs[0].to_repr()),
+        decimal(inputs[1].to_repr())
+    );
+
+    let imported_vk = VerifyingKey::<Bn256>::from_snarkjs_json(&vk_json).unwrap();
+    assert_eq!(imported_vk.ic, vk.ic);
+    assert_eq!(imported_vk.delta_g2, vk.delta_g2);
+    let imported_proof = Proof::<Bn256>::from_snarkjs_json(&proof_json).unwrap();
+    let imported_inputs = public_inputs_from_snarkjs_json(&public_json).unwrap();
+    assert_eq!(imported_inputs, inputs);
+    assert!(bool::from(verify_proof(
+        &PreparedVerifyingKey::from(&imported_vk),
+        &imported_proof,
+        &imported_inputs
+    )));
+
+    assert_eq!(
+        snarkjs_g1(
+            &serde_json::from_str::<Value>(&snarkjs_g1_json(&G1Affine::identity())).unwrap()
+        )
+        .unwrap(),
+        G1Affine::identity()
+    );
+    assert!(
+        VerifyingKey::<Bn256>::from_snarkjs_json(&vk_json.replace("bn128", "bls12381")).is_err()
+    );
+    assert!(VerifyingKey::<Bn256>::from_snarkjs_json(
+        &vk_json.replace("\"nPublic\": 2", "\"nPublic\": 3")
+    )
+    .is_err());
+    assert!(public_inputs_from_snarkjs_json("[\"-1\"]").is_err());
+}
+
+#[cfg(test)]
+fn gnark_fq_bytes(x: &Fq) -> Vec<u8> {
+    x.to_repr().iter().rev().copied().collect()
+}
+
+#[cfg(test)]
+fn gnark_g1_bytes(p: &G1Affine, compressed: bool) -> Vec<u8> {
+    let mut bytes = gnark_fq_bytes(&p.x);
+    if !compressed {
+        bytes.extend(gnark_fq_bytes(&p.y));
+    } else if bool::from(p.is_identity()) {
+        bytes[0] |= GNARK_COMPRESSED_INFINITY;
+    } else if lexicographically_largest(&p.y) {
+        bytes[0] |= GNARK_COMPRESSED_LARGEST;
+    } else {
+        bytes[0] |= GNARK_COMPRESSED_SMALLEST;
+    }
+    bytes
+}
+
+#[cfg(test)]
+fn gnark_g2_bytes(p: &G2Affine, compressed: bool) -> Vec<u8> {
+    let mut bytes = gnark_fq_bytes(&p.x.c1);
+    bytes.extend(gnark_fq_bytes(&p.x.c0));
+    if !compressed {
+        bytes.extend(gnark_fq_bytes(&p.y.c1));
+        bytes.extend(gnark_fq_bytes(&p.y.c0));
+    } else if bool::from(p.is_identity()) {
+        bytes[0] |= GNARK_COMPRESSED_INFINITY;
+    } else if lexicographically_largest_fq2(&p.y) {
+        bytes[0] |= GNARK_COMPRESSED_LARGEST;
+    } else {
+        bytes[0] |= GNARK_COMPRESSED_SMALLEST;
+    }
+    bytes
+}
+
+#[test]
+fn test_gnark_binary() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let (vk, mut forge) = forging_setup(2, XorShiftRng::from_seed([3; 16]));
+    let pvk = PreparedVerifyingKey::from(&vk);
+
+    for compressed in [true, false] {
+        let mut vk_bytes = gnark_g1_bytes(&vk.alpha_g1, compressed);
+        vk_bytes.extend(gnark_g1_bytes(&G1Affine::generator(), compressed));
+        vk_bytes.extend(gnark_g2_bytes(&vk.beta_g2, compressed));
+        vk_bytes.extend(gnark_g2_bytes(&vk.gamma_g2, compressed));
+        vk_bytes.extend(gnark_g1_bytes(&G1Affine::identity(), compressed));
+        vk_bytes.extend(gnark_g2_bytes(&vk.delta_g2, compressed));
+        vk_bytes.extend((vk.ic.len() as u32).to_be_bytes());
+        for p in vk.ic.iter() {
+            vk_bytes.extend(gnark_g1_bytes(p, compressed));
+        }
+        let imported_vk = VerifyingKey::<Bn256>::read_gnark(&mut &vk_bytes[..]).unwrap();
+        assert_eq!(imported_vk.alpha_g1, vk.alpha_g1);
+        assert_eq!(imported_vk.beta_g2, vk.beta_g2);
+        assert_eq!(imported_vk.gamma_g2, vk.gamma_g2);
+        assert_eq!(imported_vk.delta_g2, vk.delta_g2);
+        assert_eq!(imported_vk.ic, vk.ic);
+
+        for _ in 0..10 {
+            let inputs = vec![Fr::random(&mut rng), Fr::random(&mut rng)];
+            let proof = forge(&inputs);
+            let mut proof_bytes = gnark_g1_bytes(&proof.a, compressed);
+            proof_bytes.extend(gnark_g2_bytes(&proof.b, compressed));
+            proof_bytes.extend(gnark_g1_bytes(&proof.c, compressed));
+
+            let imported_proof = Proof::<Bn256>::read_gnark(&mut &proof_bytes[..]).unwrap();
+            assert_eq!(imported_proof.a, proof.a);
+            assert_eq!(imported_proof.b, proof.b);
+            assert_eq!(imported_proof.c, proof.c);
+            assert!(bool::from(verify_proof(&pvk, &imported_proof, &inputs)));
+        }
+    }
+
+    // A point off the curve is rejected
+    let mut bytes = gnark_g1_bytes(&vk.alpha_g1, false);
+    bytes[63] ^= 1;
+    assert!(read_gnark_g1(&mut &bytes[..]).is_err());
+}
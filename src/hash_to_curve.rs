@@ -0,0 +1,278 @@
+//! This module implements hashing to elliptic curves with $a = 0$ following
+//! RFC 9380, https://www.rfc-editor.org/rfc/rfc9380.html
+//!
+//! Messages are expanded with `expand_message_xmd` over SHA-256 into two
+//! field elements, which are mapped to the curve with the Shallue-van de
+//! Woestijne method, added, and multiplied by the cofactor. The constant $Z$
+//! of the map is the one found by `find_z_svdw` of the RFC. On G1 this is the
+//! suite `BN254G1_XMD:SHA-256_SVDW_RO_`, checked against gnark-crypto. On G2
+//! the points are multiplied by the full cofactor rather than the effective
+//! cofactor of other implementations, so the suite gets the identifier
+//! `BN254G2_XMD:SHA-256_SVDW-PAIRING-BN256_RO_` of this crate instead.
+//! `HashToCurve::hash_to_curve` derives the domain separation tag
+//! `{domain_prefix}-{CURVE_ID}_XMD:SHA-256_SVDW_RO_` from a prefix, while
+//! `HashToCurve::hash_to_curve_with_dst` takes the full tag.
+
+use crate::arithmetic::{CurveExt, CurveHasher, Legendre};
+use ff::Field;
+use group::cofactor::CofactorGroup;
+use sha2::{Digest, Sha256};
+use subtle::Choice;
+
+/// A field whose elements can be derived from uniformly random bytes, as
+/// needed to hash to curves over it.
+pub trait HashToField: Legendre {
+    /// The number of uniform bytes reduced into each element, which is the
+    /// size of the modulus of the prime field plus 16 bytes for each
+    /// coefficient.
+    const UNIFORM_BYTES: usize;
+
+    /// Reduces `UNIFORM_BYTES` bytes, as big endian integers, into an element.
+    fn from_uniform_bytes(bytes: &[u8]) -> Self;
+
+    /// Returns the sign of this element, as defined by `sgn0` of RFC 9380.
+    fn sgn0(&self) -> Choice;
+}
+
+/// Expands `message` into `len` uniformly random bytes with
+/// `expand_message_xmd` over SHA-256.
+///
+/// This function will panic if `len` is larger than $255 \cdot 32$ or `dst`
+/// is longer than 255 bytes.
+pub fn expand_message_xmd(message: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    let ell = (len + 31) / 32;
+    assert!(ell <= 255);
+    assert!(dst.len() <= 255);
+
+    let dst_prime = |hasher: Sha256| hasher.chain_update(dst).chain_update([dst.len() as u8]);
+
+    let b_0 = dst_prime(
+        Sha256::new()
+            .chain_update([0u8; 64])
+            .chain_update(message)
+            .chain_update((len as u16).to_be_bytes())
+            .chain_update([0u8]),
+    )
+    .finalize();
+
+    let mut uniform = Vec::with_capacity(ell * 32);
+    let mut b_i = dst_prime(Sha256::new().chain_update(b_0).chain_update([1u8])).finalize();
+    uniform.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut xored = [0u8; 32];
+        for ((x, a), b) in xored.iter_mut().zip(b_0.iter()).zip(b_i.iter()) {
+            *x = a ^ b;
+        }
+        b_i = dst_prime(Sha256::new().chain_update(xored).chain_update([i as u8])).finalize();
+        uniform.extend_from_slice(&b_i);
+    }
+
+    uniform.truncate(len);
+    uniform
+}
+
+/// The constants of the Shallue-van de Woestijne map of a curve
+/// $y^2 = x^3 + b$.
+pub(crate) struct Svdw<F> {
+    b: F,
+    z: F,
+    c1: F,
+    c2: F,
+    c3: F,
+    c4: F,
+}
+
+impl<F: HashToField> Svdw<F> {
+    fn g(&self, x: F) -> F {
+        x.square() * x + self.b
+    }
+
+    pub(crate) fn new(b: F) -> Self {
+        let mut map = Svdw {
+            b,
+            z: F::zero(),
+            c1: F::zero(),
+            c2: F::zero(),
+            c3: F::zero(),
+            c4: F::zero(),
+        };
+        let two_inv = F::one().double().invert().unwrap();
+        let three = F::one().double() + F::one();
+        let four = F::one().double().double();
+
+        // Find Z as in Appendix H.1 of RFC 9380, trying 1, -1, 2, -2, ...
+        let is_square = |x: F| bool::from(x.ct_quadratic_residue());
+        let mut ctr = F::one();
+        map.z = 'search: loop {
+            for z in [ctr, -ctr] {
+                let gz = map.g(z);
+                if bool::from(gz.is_zero()) {
+                    continue;
+                }
+                let h = -(three * z.square()) * (four * gz).invert().unwrap();
+                if bool::from(h.is_zero()) || !is_square(h) {
+                    continue;
+                }
+                if is_square(gz) || is_square(map.g(-z * two_inv)) {
+                    break 'search z;
+                }
+            }
+            ctr += F::one();
+        };
+
+        let gz = map.g(map.z);
+        let three_z2 = three * map.z.square();
+        map.c1 = gz;
+        map.c2 = -map.z * two_inv;
+        let c3 = (-gz * three_z2).sqrt().unwrap();
+        map.c3 = F::conditional_select(&c3, &-c3, c3.sgn0());
+        map.c4 = -four * gz * three_z2.invert().unwrap();
+        map
+    }
+
+    /// Maps a field element to the coordinates of a point, following
+    /// Section 6.6.1 of RFC 9380.
+    fn map_to_curve(&self, u: F) -> (F, F) {
+        let tv1 = u.square() * self.c1;
+        let tv2 = F::one() + tv1;
+        let tv1 = F::one() - tv1;
+        let tv3 = (tv1 * tv2).invert().unwrap_or(F::zero());
+        let tv4 = u * tv1 * tv3 * self.c3;
+
+        let x1 = self.c2 - tv4;
+        let e1 = self.g(x1).ct_quadratic_residue();
+        let x2 = self.c2 + tv4;
+        let e2 = self.g(x2).ct_quadratic_residue() & !e1;
+        let x3 = (tv2.square() * tv3).square() * self.c4 + self.z;
+
+        let x = F::conditional_select(&x3, &x1, e1);
+        let x = F::conditional_select(&x, &x2, e2);
+        let y = self.g(x).sqrt().unwrap();
+        let y = F::conditional_select(&-y, &y, !(u.sgn0() ^ y.sgn0()));
+        (x, y)
+    }
+}
+
+/// Returns a function hashing messages to points of `C` with the domain
+/// separation tag `dst` and the constants `map` of its Shallue-van de
+/// Woestijne map.
+pub(crate) fn hash_to_curve<'a, C>(dst: Vec<u8>, map: &'static Svdw<C::Base>) -> CurveHasher<'a, C>
+where
+    C: CurveExt + CofactorGroup<Subgroup = C>,
+    C::Base: HashToField,
+{
+    Box::new(move |message| {
+        let len = C::Base::UNIFORM_BYTES;
+        let uniform = expand_message_xmd(message, &dst, 2 * len);
+        let point = |bytes: &[u8]| {
+            let (x, y) = map.map_to_curve(C::Base::from_uniform_bytes(bytes));
+            C::new_jacobian(x, y, C::Base::one()).unwrap()
+        };
+        (point(&uniform[..len]) + point(&uniform[len..])).clear_cofactor()
+    })
+}
+
+#[cfg(test)]
+use crate::arithmetic::HashToCurve;
+#[cfg(test)]
+use crate::bn256::{Fq, Fq2, G1Affine, G1, G2};
+#[cfg(test)]
+use group::{Curve, Group};
+
+#[test]
+fn test_expand_message_xmd() {
+    // Test vectors of Appendix K.1 of RFC 9380
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    assert_eq!(
+        expand_message_xmd(b"", dst, 32),
+        [
+            0x68, 0xa9, 0x85, 0xb8, 0x7e, 0xb6, 0xb4, 0x69, 0x52, 0x12, 0x89, 0x11, 0xf2, 0xa4,
+            0x41, 0x2b, 0xbc, 0x30, 0x2a, 0x9d, 0x75, 0x96, 0x67, 0xf8, 0x7f, 0x7a, 0x21, 0xd8,
+            0x03, 0xf0, 0x72, 0x35,
+        ]
+    );
+    assert_eq!(
+        expand_message_xmd(b"abc", dst, 32),
+        [
+            0xd8, 0xcc, 0xab, 0x23, 0xb5, 0x98, 0x5c, 0xce, 0xa8, 0x65, 0xc6, 0xc9, 0x7b, 0x6e,
+            0x5b, 0x83, 0x50, 0xe7, 0x94, 0xe6, 0x03, 0xb4, 0xb9, 0x79, 0x02, 0xf5, 0x3a, 0x8a,
+            0x0d, 0x60, 0x56, 0x15,
+        ]
+    );
+
+    // The length is bound into the output
+    let long = expand_message_xmd(b"abc", dst, 100);
+    assert_eq!(long.len(), 100);
+    assert!(expand_message_xmd(b"abc", dst, 64)[..32] != long[..32]);
+}
+
+#[test]
+fn test_svdw_constants() {
+    // Z = 1 for BN254 G1
+    let map = Svdw::new(G1::b());
+    assert_eq!(map.z, Fq::one());
+    let (x, y) = map.map_to_curve(Fq::zero());
+    assert_eq!(y.square(), map.g(x));
+
+    let map = Svdw::new(G2::b());
+    for u in [Fq2::zero(), Fq2::one(), -Fq2::one()] {
+        let (x, y) = map.map_to_curve(u);
+        assert_eq!(y.square(), map.g(x));
+        assert_eq!(bool::from(u.sgn0()), bool::from(y.sgn0()));
+    }
+}
+
+#[test]
+fn test_hash_to_curve() {
+    let hash_g1 = G1::hash_to_curve("test");
+    let hash_g2 = G2::hash_to_curve("test");
+    let other_g1 = G1::hash_to_curve("other");
+
+    for message in [&b""[..], b"abc", &[0x5a; 300]] {
+        let p = hash_g1(message);
+        assert!(bool::from(p.is_on_curve()));
+        assert!(!bool::from(p.is_identity()));
+        assert_eq!(p, hash_g1(message));
+        assert!(p != other_g1(message));
+
+        let q = hash_g2(message);
+        assert!(bool::from(q.is_on_curve()));
+        assert!(!bool::from(q.is_identity()));
+        assert!(bool::from(q.is_torsion_free()));
+        assert_eq!(q.to_affine(), hash_g2(message).to_affine());
+    }
+    assert!(hash_g1(b"abc") != hash_g1(b"abd"));
+}
+
+#[test]
+fn test_hash_to_curve_vector() {
+    // The BN254G1_XMD:SHA-256_SVDW_RO_ test vector of gnark-crypto
+    let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+    let uniform = expand_message_xmd(b"", dst, 2 * Fq::UNIFORM_BYTES);
+    assert_eq!(
+        Fq::from_uniform_bytes(&uniform[..Fq::UNIFORM_BYTES]),
+        Fq::from_raw([
+            0x844feb3c67c1a9b5,
+            0xe1bd485dca804487,
+            0xd4d249737498cc27,
+            0x2f87b81d9d6ef05a,
+        ])
+    );
+    assert_eq!(
+        G1::hash_to_curve_with_dst(dst)(b"").to_affine(),
+        G1Affine {
+            x: Fq::from_raw([
+                0x8521f20749e59e86,
+                0xc42aef256a54bbd4,
+                0xf9638d376514dbf8,
+                0x0a976ab906170db1,
+            ]),
+            y: Fq::from_raw([
+                0xab2210ad4c4d53d5,
+                0xb55f6619ab59bc1f,
+                0xfc309b014398640a,
+                0x02925ead66b9e68b,
+            ]),
+        }
+    );
+}
@@ -0,0 +1,614 @@
+//! This module implements the polynomial commitment scheme of Kate, Zaverucha
+//! and Goldberg over any pairing-friendly engine,
+//! https://www.iacr.org/archive/asiacrypt2010/6477178/6477178.pdf
+//!
+//! Several polynomials can be opened at several points and checked with a
+//! single final exponentiation, either with the multi-point opening of
+//! Gabizon, Williamson and Ciobotaru (GWC), https://eprint.iacr.org/2019/953.pdf,
+//! which produces one witness per distinct point, or with that of Boneh,
+//! Drake, Fisch and Gabizon (SHPLONK), https://eprint.iacr.org/2020/081.pdf,
+//! which produces two witnesses in total.
+//!
+//! Challenges are taken as arguments and are expected to be derived from a
+//! transcript by the caller.
+
+use crate::arithmetic::{
+    best_multiexp, parallelize, Coeff, CurveAffine, Engine, FieldExt, MultiMillerLoop, Polynomial,
+};
+use crate::srs::{self, Validation};
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+use rand_core::RngCore;
+use std::io;
+use subtle::Choice;
+
+/// The structured reference string of the scheme: the powers $[s^i] G_1$
+/// for $0 \le i < 2^k$, together with $H = G_2$ and $[s] H$.
+#[derive(Clone, Debug)]
+pub struct Params<E: Engine> {
+    k: u32,
+    g: Vec<E::G1Affine>,
+    g2: E::G2Affine,
+    s_g2: E::G2Affine,
+}
+
+impl<E: Engine> Params<E> {
+    /// Generates parameters for polynomials of degree less than $2^k$ from
+    /// the secret `s`. Anyone who knows `s` can forge openings, so this is
+    /// intended for testing only.
+    pub fn unsafe_setup_with_s(k: u32, s: E::Scalar) -> Self {
+        let n = 1usize << k;
+
+        let powers: Vec<_> =
+            std::iter::successors(Some(E::Scalar::one()), |power| Some(*power * s))
+                .take(n)
+                .collect();
+
+        let g1 = E::G1Affine::generator();
+        let mut g = vec![E::G1::identity(); n];
+        parallelize(&mut g, |chunk, start| {
+            for (g, power) in chunk.iter_mut().zip(powers[start..].iter()) {
+                *g = g1 * power;
+            }
+        });
+        let mut g_affine = vec![E::G1Affine::identity(); n];
+        E::G1::batch_normalize(&g, &mut g_affine);
+
+        let g2 = E::G2Affine::generator();
+        let s_g2 = (g2 * s).to_affine();
+
+        Params {
+            k,
+            g: g_affine,
+            g2,
+            s_g2,
+        }
+    }
+
+    /// Generates parameters for polynomials of degree less than $2^k$ from a
+    /// secret sampled from `rng`. This is intended for testing only.
+    pub fn unsafe_setup(k: u32, rng: impl RngCore) -> Self {
+        Self::unsafe_setup_with_s(k, E::Scalar::random(rng))
+    }
+
+    /// Creates parameters from the powers $[s^i] G_1$, $G_2$ and $[s] G_2$
+    /// of an existing setup, such as a powers-of-tau ceremony.
+    ///
+    /// This function will panic if the number of powers is not a power of
+    /// two.
+    pub fn from_parts(g: Vec<E::G1Affine>, g2: E::G2Affine, s_g2: E::G2Affine) -> Self {
+        assert!(g.len().is_power_of_two());
+        Params {
+            k: g.len().trailing_zeros(),
+            g,
+            g2,
+            s_g2,
+        }
+    }
+
+    /// Shrinks the parameters to polynomials of degree less than $2^k$.
+    ///
+    /// This function will panic if `k` is larger than the current size.
+    pub fn downsize(&mut self, k: u32) {
+        assert!(k <= self.k);
+        self.k = k;
+        self.g.truncate(1 << k);
+    }
+
+    /// Returns the logarithm of the number of powers.
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// Returns the number of powers, which bounds the number of
+    /// coefficients of committed polynomials.
+    pub fn n(&self) -> usize {
+        self.g.len()
+    }
+
+    /// Returns the powers $[s^i] G_1$.
+    pub fn g(&self) -> &[E::G1Affine] {
+        &self.g
+    }
+
+    /// Returns the generator $H$ of $G_2$.
+    pub fn g2(&self) -> &E::G2Affine {
+        &self.g2
+    }
+
+    /// Returns $[s] H$.
+    pub fn s_g2(&self) -> &E::G2Affine {
+        &self.s_g2
+    }
+
+    /// Writes the parameters in the layout of halo2's `ParamsKZG` without
+    /// the Lagrange basis: $k$ as a little endian `u32`, the $2^k$ powers
+    /// $[s^i] G_1$, $H$ and $[s] H$, all with compressed encodings. The full
+    /// layout is written by [`write_halo2`](crate::srs::write_halo2).
+    pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        srs::write_params(self, writer, false)
+    }
+
+    /// Reads parameters written by `write`. The powers are checked with
+    /// `validation`, while $H$ and $[s] H$ are always checked to be in the
+    /// prime order subgroup. $k$ must be at most the 2-adicity of the
+    /// scalar field.
+    pub fn read<R: io::Read>(reader: &mut R, validation: Validation) -> io::Result<Self> {
+        srs::read_params(reader, validation, false)
+    }
+
+    /// Commits to a polynomial.
+    ///
+    /// This function will panic if the polynomial has more than $n$
+    /// coefficients.
+    pub fn commit(&self, poly: &Polynomial<E::Scalar, Coeff>) -> E::G1Affine {
+        assert!(poly.len() <= self.g.len());
+        best_multiexp(poly.as_ref(), &self.g[..poly.len()]).to_affine()
+    }
+
+    /// Opens a polynomial at `point`, returning the witness
+    /// $[(p(s) - p(z)) / (s - z)] G_1$ and the evaluation $p(z)$.
+    pub fn open(
+        &self,
+        poly: &Polynomial<E::Scalar, Coeff>,
+        point: E::Scalar,
+    ) -> (E::G1Affine, E::Scalar) {
+        let (quotient, eval) = poly.divide_by_linear(point);
+        (self.commit(&quotient), eval)
+    }
+
+    /// Opens the queried polynomials with the multi-point scheme of GWC,
+    /// returning one witness for each distinct point, in the order in which
+    /// the points first appear. The polynomials queried at the same point
+    /// are combined with the powers of `v`.
+    pub fn open_gwc(&self, queries: &[ProverQuery<E::Scalar>], v: E::Scalar) -> Vec<E::G1Affine> {
+        group_by(queries, |a, b| a.point == b.point)
+            .into_iter()
+            .map(|queries| {
+                let mut combined = Polynomial::zero();
+                for query in queries.iter().rev() {
+                    combined = &combined.scale(v) + query.poly;
+                }
+                self.open(&combined, queries[0].point).0
+            })
+            .collect()
+    }
+
+    /// Opens the queried polynomials with the multi-point scheme of SHPLONK,
+    /// returning its two witnesses. The polynomials are combined with the
+    /// powers of `gamma`, and the evaluation point of the combination is
+    /// derived from the first witness by `challenge`.
+    pub fn open_shplonk(
+        &self,
+        queries: &[ProverQuery<E::Scalar>],
+        gamma: E::Scalar,
+        challenge: impl FnOnce(&E::G1Affine) -> E::Scalar,
+    ) -> (E::G1Affine, E::G1Affine) {
+        // The polynomials f_i with their sets of points T_i, and the union T
+        let sets: Vec<_> = group_by(queries, |a, b| a.poly == b.poly)
+            .into_iter()
+            .map(|queries| {
+                let points: Vec<_> = group_by(&queries, |a, b| a.point == b.point)
+                    .into_iter()
+                    .map(|queries| queries[0].point)
+                    .collect();
+                (queries[0].poly, points)
+            })
+            .collect();
+        let all_points = union(sets.iter().map(|(_, points)| points));
+
+        // h = sum_i gamma^i Z_{T \ T_i} (f_i - r_i) / Z_T, where r_i
+        // interpolates f_i over T_i
+        let mut numerator = Polynomial::zero();
+        let mut remainders = vec![];
+        let mut gamma_power = E::Scalar::one();
+        for (poly, points) in sets.iter() {
+            let evals: Vec<_> = points.iter().map(|x| (*x, poly.evaluate(*x))).collect();
+            let remainder = Polynomial::interpolate(&evals);
+            let term = &vanishing_poly(&difference(&all_points, points)) * &(*poly - &remainder);
+            numerator = &numerator + &term.scale(gamma_power);
+            remainders.push(remainder);
+            gamma_power *= gamma;
+        }
+        let h = all_points.iter().fold(numerator, |h, x| {
+            let (quotient, _remainder) = h.divide_by_linear(*x);
+            debug_assert!(bool::from(_remainder.is_zero()));
+            quotient
+        });
+        let w = self.commit(&h);
+
+        // L = sum_i gamma^i Z_{T \ T_i}(z) (f_i - r_i(z)) - Z_T(z) h vanishes
+        // at z
+        let z = challenge(&w);
+        let mut linearized = h.scale(-eval_vanishing(&all_points, z));
+        let mut gamma_power = E::Scalar::one();
+        for ((poly, points), remainder) in sets.iter().zip(remainders.iter()) {
+            let scale = gamma_power * eval_vanishing(&difference(&all_points, points), z);
+            let term = *poly - &Polynomial::from_coeffs(vec![remainder.evaluate(z)]);
+            linearized = &linearized + &term.scale(scale);
+            gamma_power *= gamma;
+        }
+        let (w_prime, _) = self.open(&linearized, z);
+
+        (w, w_prime)
+    }
+}
+
+impl<E: MultiMillerLoop> Params<E> {
+    /// Checks that `witness` proves that the polynomial committed to by
+    /// `commitment` evaluates to `eval` at `point`, i.e. that
+    /// $$e(C - [v] G_1 + [z] W, H) = e(W, [s] H).$$
+    pub fn verify(
+        &self,
+        commitment: &E::G1Affine,
+        point: E::Scalar,
+        eval: E::Scalar,
+        witness: &E::G1Affine,
+    ) -> Choice {
+        let lhs = best_multiexp(
+            &[E::Scalar::one(), -eval, point],
+            &[*commitment, self.g[0], *witness],
+        )
+        .to_affine();
+        self.pairing_check(&lhs, witness)
+    }
+
+    /// Checks the witnesses produced by `open_gwc` for the queries with the
+    /// same `v`. The checks of the distinct points are combined with the
+    /// powers of a challenge sampled from `rng`.
+    pub fn verify_gwc(
+        &self,
+        queries: &[VerifierQuery<E::G1Affine>],
+        v: E::Scalar,
+        witnesses: &[E::G1Affine],
+        mut rng: impl RngCore,
+    ) -> Choice {
+        let groups = group_by(queries, |a, b| a.point == b.point);
+        if groups.len() != witnesses.len() {
+            return Choice::from(0);
+        }
+        let u = E::Scalar::random(&mut rng);
+
+        // sum_j u^j (C_j - [v_j] G_1 + [z_j] W_j) and sum_j u^j W_j, where
+        // C_j and v_j combine the queries at z_j with the powers of v
+        let mut bases = vec![];
+        let mut scalars = vec![];
+        let mut rhs_scalars = vec![];
+        let mut eval = E::Scalar::zero();
+        let mut u_power = E::Scalar::one();
+        for (queries, witness) in groups.iter().zip(witnesses.iter()) {
+            let mut scale = u_power;
+            for query in queries {
+                bases.push(query.commitment);
+                scalars.push(scale);
+                eval += scale * query.eval;
+                scale *= v;
+            }
+            bases.push(*witness);
+            scalars.push(u_power * queries[0].point);
+            rhs_scalars.push(u_power);
+            u_power *= u;
+        }
+        bases.push(self.g[0]);
+        scalars.push(-eval);
+
+        let lhs = best_multiexp(&scalars, &bases).to_affine();
+        let rhs = best_multiexp(&rhs_scalars, witnesses).to_affine();
+        self.pairing_check(&lhs, &rhs)
+    }
+
+    /// Checks the witnesses produced by `open_shplonk` for the queries with
+    /// the same `gamma`, where `z` is the evaluation point derived from the
+    /// first witness.
+    pub fn verify_shplonk(
+        &self,
+        queries: &[VerifierQuery<E::G1Affine>],
+        gamma: E::Scalar,
+        z: E::Scalar,
+        witnesses: &(E::G1Affine, E::G1Affine),
+    ) -> Choice {
+        // The commitments C_i with their evaluations over T_i, and the union T
+        let mut sets = vec![];
+        for queries in group_by(queries, |a, b| a.commitment == b.commitment) {
+            let mut evals = vec![];
+            for queries in group_by(&queries, |a, b| a.point == b.point) {
+                // Conflicting claims cannot both hold
+                if queries.iter().any(|query| query.eval != queries[0].eval) {
+                    return Choice::from(0);
+                }
+                evals.push((queries[0].point, queries[0].eval));
+            }
+            sets.push((queries[0].commitment, evals));
+        }
+        let all_points = union(
+            sets.iter()
+                .map(|(_, evals)| evals.iter().map(|(x, _)| *x).collect::<Vec<_>>()),
+        );
+
+        // sum_i gamma^i Z_{T \ T_i}(z) (C_i - [r_i(z)] G_1) - Z_T(z) W + [z] W'
+        let mut bases = vec![];
+        let mut scalars = vec![];
+        let mut eval = E::Scalar::zero();
+        let mut gamma_power = E::Scalar::one();
+        for (commitment, evals) in sets.iter() {
+            let points: Vec<_> = evals.iter().map(|(x, _)| *x).collect();
+            let scale = gamma_power * eval_vanishing(&difference(&all_points, &points), z);
+            bases.push(*commitment);
+            scalars.push(scale);
+            eval += scale * Polynomial::interpolate(evals).evaluate(z);
+            gamma_power *= gamma;
+        }
+        let (w, w_prime) = *witnesses;
+        bases.extend([self.g[0], w, w_prime]);
+        scalars.extend([-eval, -eval_vanishing(&all_points, z), z]);
+
+        let lhs = best_multiexp(&scalars, &bases).to_affine();
+        self.pairing_check(&lhs, &w_prime)
+    }
+
+    /// Checks that $$e(lhs, H) = e(rhs, [s] H)$$ with a single final
+    /// exponentiation.
+    fn pairing_check(&self, lhs: &E::G1Affine, rhs: &E::G1Affine) -> Choice {
+        let neg_rhs = -*rhs;
+        let g2 = E::G2Prepared::from(self.g2);
+        let s_g2 = E::G2Prepared::from(self.s_g2);
+        E::pairing_check(&[(lhs, &g2), (&neg_rhs, &s_g2)])
+    }
+}
+
+/// A polynomial queried at a point, on the prover's side.
+#[derive(Clone, Copy, Debug)]
+pub struct ProverQuery<'a, F: FieldExt> {
+    /// The queried polynomial.
+    pub poly: &'a Polynomial<F, Coeff>,
+    /// The point at which it is opened.
+    pub point: F,
+}
+
+/// A claimed evaluation of a committed polynomial, on the verifier's side.
+#[derive(Clone, Copy, Debug)]
+pub struct VerifierQuery<C: CurveAffine> {
+    /// The commitment to the queried polynomial.
+    pub commitment: C,
+    /// The point at which it is opened.
+    pub point: C::ScalarExt,
+    /// The claimed evaluation.
+    pub eval: C::ScalarExt,
+}
+
+/// Splits `items` into the classes of `eq`, in the order in which they first
+/// appear.
+fn group_by<T>(items: &[T], eq: impl Fn(&T, &T) -> bool) -> Vec<Vec<&T>> {
+    let mut groups: Vec<Vec<&T>> = vec![];
+    for item in items {
+        match groups.iter_mut().find(|group| eq(group[0], item)) {
+            Some(group) => group.push(item),
+            None => groups.push(vec![item]),
+        }
+    }
+    groups
+}
+
+/// Returns the distinct points of all sets.
+fn union<F: FieldExt, S: AsRef<[F]>>(sets: impl Iterator<Item = S>) -> Vec<F> {
+    let mut points = vec![];
+    for set in sets {
+        for x in set.as_ref() {
+            if !points.contains(x) {
+                points.push(*x);
+            }
+        }
+    }
+    points
+}
+
+/// Returns the points of `a` that are not in `b`.
+fn difference<F: FieldExt>(a: &[F], b: &[F]) -> Vec<F> {
+    a.iter().filter(|x| !b.contains(x)).copied().collect()
+}
+
+/// Returns the product of $X - x$ over the points.
+fn vanishing_poly<F: FieldExt>(points: &[F]) -> Polynomial<F, Coeff> {
+    points
+        .iter()
+        .fold(Polynomial::from_coeffs(vec![F::one()]), |acc, x| {
+            &acc * &Polynomial::from_coeffs(vec![-*x, F::one()])
+        })
+}
+
+/// Returns the product of $z - x$ over the points.
+fn eval_vanishing<F: FieldExt>(points: &[F], z: F) -> F {
+    points.iter().fold(F::one(), |acc, x| acc * (z - x))
+}
+
+#[cfg(test)]
+use crate::bn256::{Bn256, Fr, G1Affine, G2};
+#[cfg(test)]
+use ff::PrimeField;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[cfg(test)]
+fn random_poly(n: usize, mut rng: impl RngCore) -> Polynomial<Fr, Coeff> {
+    Polynomial::from_coeffs((0..n).map(|_| Fr::random(&mut rng)).collect())
+}
+
+#[test]
+fn test_commit_open_verify() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let s = Fr::random(&mut rng);
+    let params = Params::<Bn256>::unsafe_setup_with_s(4, s);
+    assert_eq!(params.n(), 16);
+
+    let poly = random_poly(16, &mut rng);
+    let commitment = params.commit(&poly);
+    assert_eq!(
+        commitment,
+        (G1Affine::generator() * poly.evaluate(s)).to_affine()
+    );
+
+    let point = Fr::random(&mut rng);
+    let (witness, eval) = params.open(&poly, point);
+    assert_eq!(eval, poly.evaluate(point));
+    assert!(bool::from(params.verify(
+        &commitment,
+        point,
+        eval,
+        &witness
+    )));
+
+    assert!(!bool::from(params.verify(
+        &commitment,
+        point,
+        eval + Fr::one(),
+        &witness
+    )));
+    assert!(!bool::from(params.verify(
+        &commitment,
+        point + Fr::one(),
+        eval,
+        &witness
+    )));
+    let other = params.commit(&random_poly(16, &mut rng));
+    assert!(!bool::from(params.verify(&other, point, eval, &witness)));
+
+    // Round trip through the serialized form
+    let mut bytes = vec![];
+    params.write(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 4 + 16 * 32 + 2 * 64);
+    let read = Params::<Bn256>::read(&mut &bytes[..], Validation::OnCurve).unwrap();
+    assert_eq!(read.g(), params.g());
+    assert_eq!(read.g2(), params.g2());
+    assert_eq!(read.s_g2(), params.s_g2());
+    assert!(Params::<Bn256>::read(&mut &bytes[..bytes.len() - 1], Validation::OnCurve).is_err());
+
+    // Sizes beyond the 2-adicity of the scalar field are rejected
+    let mut large = bytes.clone();
+    large[..4].copy_from_slice(&(Fr::S + 1).to_le_bytes());
+    assert!(Params::<Bn256>::read(&mut &large[..], Validation::OnCurve).is_err());
+
+    // and so is a G2 point outside the prime order subgroup, whatever the
+    // validation of the powers
+    let outside = <G2 as group::Group>::random(&mut rng).to_affine();
+    let mut bytes = vec![];
+    Params::<Bn256>::from_parts(params.g().to_vec(), *params.g2(), outside)
+        .write(&mut bytes)
+        .unwrap();
+    assert!(Params::<Bn256>::read(&mut &bytes[..], Validation::OnCurve).is_err());
+
+    let mut small = params.clone();
+    small.downsize(2);
+    assert_eq!(small.g(), &params.g()[..4]);
+}
+
+#[test]
+fn test_multi_open() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let params = Params::<Bn256>::unsafe_setup(5, &mut rng);
+
+    let polys: Vec<_> = [32, 20, 7, 1]
+        .iter()
+        .map(|n| random_poly(*n, &mut rng))
+        .collect();
+    let commitments: Vec<_> = polys.iter().map(|poly| params.commit(poly)).collect();
+    let points: Vec<_> = (0..3).map(|_| Fr::random(&mut rng)).collect();
+
+    // Polynomials opened at overlapping sets of points
+    let indices = [
+        (0, points[0]),
+        (1, points[0]),
+        (1, points[1]),
+        (2, points[2]),
+        (0, points[1]),
+        (3, points[0]),
+        (3, points[1]),
+        (3, points[2]),
+    ];
+    let prover_queries: Vec<_> = indices
+        .iter()
+        .map(|(i, point)| ProverQuery {
+            poly: &polys[*i],
+            point: *point,
+        })
+        .collect();
+    let verifier_queries: Vec<_> = indices
+        .iter()
+        .map(|(i, point)| VerifierQuery {
+            commitment: commitments[*i],
+            point: *point,
+            eval: polys[*i].evaluate(*point),
+        })
+        .collect();
+    let mut invalid_queries = verifier_queries.clone();
+    invalid_queries[4].eval += Fr::one();
+
+    // GWC
+    let v = Fr::random(&mut rng);
+    let witnesses = params.open_gwc(&prover_queries, v);
+    assert_eq!(witnesses.len(), 3);
+    assert!(bool::from(params.verify_gwc(
+        &verifier_queries,
+        v,
+        &witnesses,
+        &mut rng
+    )));
+    assert!(!bool::from(params.verify_gwc(
+        &invalid_queries,
+        v,
+        &witnesses,
+        &mut rng
+    )));
+    assert!(!bool::from(params.verify_gwc(
+        &verifier_queries,
+        v + Fr::one(),
+        &witnesses,
+        &mut rng
+    )));
+    assert!(!bool::from(params.verify_gwc(
+        &verifier_queries,
+        v,
+        &witnesses[..2],
+        &mut rng
+    )));
+
+    // SHPLONK
+    let gamma = Fr::random(&mut rng);
+    let z = Fr::random(&mut rng);
+    let witnesses = params.open_shplonk(&prover_queries, gamma, |_| z);
+    assert!(bool::from(params.verify_shplonk(
+        &verifier_queries,
+        gamma,
+        z,
+        &witnesses
+    )));
+    assert!(!bool::from(params.verify_shplonk(
+        &invalid_queries,
+        gamma,
+        z,
+        &witnesses
+    )));
+    assert!(!bool::from(params.verify_shplonk(
+        &verifier_queries,
+        gamma,
+        z + Fr::one(),
+        &witnesses
+    )));
+
+    // A repeated query must agree with the first one
+    let mut conflicting = verifier_queries.clone();
+    conflicting.push(invalid_queries[4]);
+    assert!(!bool::from(params.verify_shplonk(
+        &conflicting,
+        gamma,
+        z,
+        &witnesses
+    )));
+}
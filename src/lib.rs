@@ -8,6 +8,13 @@ mod binops;
 pub mod arithmetic;
 pub mod bn256;
 pub mod bls12_381;
+pub mod bls_sig;
+pub mod groth16;
+pub mod hash_to_curve;
+pub mod kzg;
+pub mod pedersen;
+pub mod srs;
+pub mod transcript;
 
 pub extern crate group;
 
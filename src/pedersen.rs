@@ -0,0 +1,309 @@
+//! This module implements Pedersen vector commitments
+//! $$C = \sum_i m_i G_i + r H$$
+//! with generators that are derived by hashing to the curve, so that nobody
+//! knows discrete logarithms between them.
+//!
+//! For a domain separator `domain`, the generators are derived with
+//! [`HashToCurve::hash_to_curve`] under the domain prefix `domain`, that is with
+//! the tag `{domain}-{CURVE_ID}_XMD:SHA-256_SVDW_RO_` of RFC 9380:
+//!
+//! - $G_i$ is the hash of the 4 byte big endian encoding of $i$, for
+//!   $i = 0, 1, \ldots, n - 1$;
+//! - $H$ is the hash of the 8 bytes `blinding`.
+//!
+//! The generators of a vector of length $n$ are thus a prefix of those of any
+//! longer vector in the same domain.
+
+use crate::arithmetic::{best_multiexp, CurveAffine, HashToCurve};
+use ff::PrimeField;
+use group::{Curve, Group};
+
+/// Vectors of at most this length get fixed-base tables, which make
+/// commitments cost additions only.
+pub const FIXED_BASE_MAX_LEN: usize = 16;
+
+/// The width in bits of the windows of the fixed-base tables.
+const WINDOW: usize = 4;
+
+/// The generators $G_0, \ldots, G_{n - 1}$ and $H$ of a domain.
+#[derive(Clone, Debug)]
+pub struct Generators<C: CurveAffine> {
+    g: Vec<C>,
+    h: C,
+    tables: Option<Vec<FixedBaseTable<C>>>,
+}
+
+/// The multiples $[k \cdot 2^{4 w}] P$ of a point $P$ for every window $w$
+/// and $1 \le k < 16$.
+#[derive(Clone, Debug)]
+struct FixedBaseTable<C: CurveAffine>(Vec<C>);
+
+impl<C: CurveAffine> FixedBaseTable<C> {
+    fn windows() -> usize {
+        (C::ScalarExt::NUM_BITS as usize + WINDOW - 1) / WINDOW
+    }
+
+    fn new(point: &C) -> Self {
+        // `add_mul` needs a little endian `to_repr`
+        assert_eq!(C::ScalarExt::from(1).to_repr().as_ref()[0], 1);
+
+        let per_window = (1 << WINDOW) - 1;
+        let mut multiples = Vec::with_capacity(Self::windows() * per_window);
+        let mut base = point.to_curve();
+        for _ in 0..Self::windows() {
+            let mut multiple = base;
+            for _ in 0..per_window {
+                multiples.push(multiple);
+                multiple += base;
+            }
+            base = multiple;
+        }
+
+        let mut affine = vec![C::identity(); multiples.len()];
+        C::Curve::batch_normalize(&multiples, &mut affine);
+        FixedBaseTable(affine)
+    }
+
+    /// Adds $[s] P$ to `acc`. The windows are read from `to_repr`, which
+    /// must be little endian, as it is for the scalar fields of this crate.
+    fn add_mul(&self, acc: &mut C::Curve, scalar: &C::ScalarExt) {
+        let per_window = (1 << WINDOW) - 1;
+        let repr = scalar.to_repr();
+        for (w, byte) in repr
+            .as_ref()
+            .iter()
+            .flat_map(|byte| [byte & 0x0f, byte >> 4])
+            .take(Self::windows())
+            .enumerate()
+        {
+            if byte != 0 {
+                *acc += self.0[w * per_window + byte as usize - 1];
+            }
+        }
+    }
+}
+
+impl<C: CurveAffine> Generators<C>
+where
+    C::CurveExt: HashToCurve,
+{
+    /// Derives the $n$ generators $G_i$ and $H$ of `domain`. Fixed-base
+    /// tables are computed when $n$ is at most [`FIXED_BASE_MAX_LEN`].
+    pub fn new(domain: &str, n: usize) -> Self {
+        let hasher = C::CurveExt::hash_to_curve(domain);
+        let mut points: Vec<_> = (0..n as u32).map(|i| hasher(&i.to_be_bytes())).collect();
+        points.push(hasher(b"blinding"));
+
+        let mut affine = vec![C::identity(); points.len()];
+        C::Curve::batch_normalize(&points, &mut affine);
+        let h = affine.pop().unwrap();
+
+        let tables = if n <= FIXED_BASE_MAX_LEN {
+            Some(
+                affine
+                    .iter()
+                    .chain(Some(&h))
+                    .map(FixedBaseTable::new)
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        Generators {
+            g: affine,
+            h,
+            tables,
+        }
+    }
+
+    /// Returns the number of generators $G_i$.
+    pub fn len(&self) -> usize {
+        self.g.len()
+    }
+
+    /// Returns whether there are no generators $G_i$.
+    pub fn is_empty(&self) -> bool {
+        self.g.is_empty()
+    }
+
+    /// Returns the generators $G_i$.
+    pub fn g(&self) -> &[C] {
+        &self.g
+    }
+
+    /// Returns the blinding generator $H$.
+    pub fn h(&self) -> C {
+        self.h
+    }
+
+    /// Commits to `values` with the blinding factor `blind`. Vectors shorter
+    /// than the generators use their prefix.
+    ///
+    /// This function will panic if there are more values than generators.
+    /// Like `best_multiexp`, it is not constant time.
+    pub fn commit(&self, values: &[C::ScalarExt], blind: C::ScalarExt) -> C {
+        assert!(values.len() <= self.g.len());
+
+        match &self.tables {
+            Some(tables) => {
+                let mut acc = C::Curve::identity();
+                for (table, value) in tables.iter().zip(values.iter()) {
+                    table.add_mul(&mut acc, value);
+                }
+                tables[self.g.len()].add_mul(&mut acc, &blind);
+                acc.to_affine()
+            }
+            None => {
+                let mut scalars = values.to_vec();
+                scalars.push(blind);
+                let mut bases = self.g[..values.len()].to_vec();
+                bases.push(self.h);
+                best_multiexp(&scalars, &bases).to_affine()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::bn256::{Fq, Fr, G1Affine, G1};
+#[cfg(test)]
+use ff::Field;
+#[cfg(test)]
+use group::prime::PrimeCurveAffine;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn test_generators() {
+    let generators = Generators::<G1Affine>::new("pedersen", 20);
+    assert_eq!(generators.len(), 20);
+
+    // The derivation documented above
+    let hasher = G1::hash_to_curve("pedersen");
+    for (i, g) in generators.g().iter().enumerate() {
+        assert_eq!(*g, hasher(&(i as u32).to_be_bytes()).to_affine());
+        assert!(!bool::from(g.is_identity()));
+    }
+    assert_eq!(generators.h(), hasher(b"blinding").to_affine());
+
+    // Pinned, so that changes to the derivation are noticed
+    let pinned = Generators::<G1Affine>::new("pedersen", 2);
+    assert_eq!(
+        pinned.g(),
+        [
+            G1Affine {
+                x: Fq::from_raw([
+                    0x3b25f15009540bb6,
+                    0xabee8157ea1b7a19,
+                    0xc337b3e25ec78811,
+                    0x2dda81307533964b,
+                ]),
+                y: Fq::from_raw([
+                    0x901d0bfcb844caff,
+                    0x861e5f4210a98535,
+                    0xddf611d197484dcc,
+                    0x0c9573d13f4dc6ea,
+                ]),
+            },
+            G1Affine {
+                x: Fq::from_raw([
+                    0x9cd9a255bc94307e,
+                    0x1c4c080d624c135d,
+                    0xcd1b98764319b067,
+                    0x215f4faaebd89a2d,
+                ]),
+                y: Fq::from_raw([
+                    0x6b3bfdfb9c10dc6e,
+                    0xf72f458523f03ac4,
+                    0x085f37c8957c6da0,
+                    0x10815936b8c79abf,
+                ]),
+            },
+        ]
+    );
+    assert_eq!(
+        pinned.h(),
+        G1Affine {
+            x: Fq::from_raw([
+                0x67db52d298879a41,
+                0x2532cf6559d805c8,
+                0x471b54b1f74feaed,
+                0x0787ce0cc794bce3,
+            ]),
+            y: Fq::from_raw([
+                0x5dde8647759a4c1c,
+                0x383c57c95cb340e0,
+                0xc2d5d390519e0e19,
+                0x22ccfa947d1f5036,
+            ]),
+        }
+    );
+
+    // Prefixes of longer vectors, and distinct across domains
+    let short = Generators::<G1Affine>::new("pedersen", 3);
+    assert_eq!(short.g(), &generators.g()[..3]);
+    assert_eq!(short.h(), generators.h());
+    let other = Generators::<G1Affine>::new("other", 3);
+    assert!(other.g()[0] != short.g()[0]);
+    assert!(other.h() != short.h());
+
+    let mut all = generators.g().to_vec();
+    all.push(generators.h());
+    for (i, a) in all.iter().enumerate() {
+        for b in all[..i].iter() {
+            assert!(a != b);
+        }
+    }
+}
+
+#[test]
+fn test_commit() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // Both the fixed-base and the multiexp paths
+    for n in [FIXED_BASE_MAX_LEN, FIXED_BASE_MAX_LEN + 1] {
+        let generators = Generators::<G1Affine>::new("pedersen", n);
+        assert_eq!(generators.tables.is_some(), n <= FIXED_BASE_MAX_LEN);
+
+        let a: Vec<_> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+        let b: Vec<_> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+        let (r, s) = (Fr::random(&mut rng), Fr::random(&mut rng));
+
+        let expected = a
+            .iter()
+            .zip(generators.g().iter())
+            .fold(generators.h() * r, |acc, (a, g)| acc + *g * a);
+        let commitment = generators.commit(&a, r);
+        assert_eq!(commitment, expected.to_affine());
+
+        // Commitments are additively homomorphic
+        let sum: Vec<_> = a.iter().zip(b.iter()).map(|(a, b)| *a + b).collect();
+        assert_eq!(
+            generators.commit(&sum, r + s),
+            (commitment + generators.commit(&b, s)).to_affine()
+        );
+
+        // Shorter vectors use a prefix of the generators
+        let prefix = generators.commit(&a[..3], r);
+        assert_eq!(
+            prefix,
+            (generators.g()[0] * a[0]
+                + generators.g()[1] * a[1]
+                + generators.g()[2] * a[2]
+                + generators.h() * r)
+                .to_affine()
+        );
+
+        assert_eq!(generators.commit(&[], Fr::zero()), G1Affine::identity());
+        assert_eq!(
+            generators.commit(&[-Fr::one()], Fr::zero()),
+            -generators.g()[0]
+        );
+    }
+}
@@ -0,0 +1,671 @@
+//! This module reads and writes the structured reference strings produced by
+//! powers-of-tau ceremonies.
+//!
+//! Two layouts are supported: the `.ptau` files of snarkjs, in which the
+//! Perpetual Powers of Tau and Hermez ceremonies over BN254 are published,
+//! and the layout of halo2's `ParamsKZG` with compressed points. Both are
+//! read and written point by point, without buffering the whole file.
+//! [`Params::write`] uses the latter without the Lagrange basis.
+
+use crate::arithmetic::{
+    best_multiexp, is_torsion_free, CurveAffine, Engine, EvaluationDomain, MultiMillerLoop,
+};
+use crate::bn256::{Bn256, Fq, Fq2, Fr, G1Affine, G2Affine, G2Prepared};
+use crate::kzg::Params;
+use ff::{Field, PrimeField};
+use group::{prime::PrimeCurveAffine, Curve, GroupEncoding};
+use rand_core::RngCore;
+use std::convert::TryInto;
+use std::io;
+use subtle::Choice;
+
+/// The checks performed on the points of a loaded SRS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validation {
+    /// Points are only checked to be valid encodings of points on the curve.
+    OnCurve,
+    /// Points are also checked to be in the prime order subgroup.
+    Subgroup,
+}
+
+impl Validation {
+    fn check<C: CurveAffine>(self, point: C) -> io::Result<C> {
+        if self == Validation::Subgroup && !is_torsion_free(&point) {
+            return Err(invalid_data("point is not in the prime order subgroup"));
+        }
+        Ok(point)
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Checks that `g` holds consecutive powers of the secret $s$ of `s_g2`,
+/// $$e(g_i, [s] H) = e(g_{i + 1}, H)$$ for all $i$, where $H$ is `g2`. The
+/// equations are combined with random scalars sampled from `rng`, so that a
+/// single pairing check is needed.
+pub fn check_powers<E: MultiMillerLoop>(
+    g: &[E::G1Affine],
+    g2: &E::G2Affine,
+    s_g2: &E::G2Affine,
+    rng: impl RngCore,
+) -> Choice {
+    let (lhs, rhs) = shifted_combination(g, rng);
+    let neg_rhs = -rhs;
+    E::pairing_check(&[
+        (&lhs, &E::G2Prepared::from(*s_g2)),
+        (&neg_rhs, &E::G2Prepared::from(*g2)),
+    ])
+}
+
+/// Returns $\sum_i r_i p_i$ and $\sum_i r_i p_{i + 1}$ for random scalars
+/// $r_i$.
+fn shifted_combination<C: CurveAffine>(points: &[C], mut rng: impl RngCore) -> (C, C) {
+    if points.len() < 2 {
+        return (C::identity(), C::identity());
+    }
+    let scalars: Vec<_> = (1..points.len())
+        .map(|_| C::ScalarExt::random(&mut rng))
+        .collect();
+    let n = points.len() - 1;
+    (
+        best_multiexp(&scalars, &points[..n]).to_affine(),
+        best_multiexp(&scalars, &points[1..]).to_affine(),
+    )
+}
+
+/// Returns the commitments $[L_i(s)] G_1$ to the Lagrange basis polynomials
+/// of the domain of size $n$, given the powers $[s^i] G_1$ for $i < n$.
+///
+/// This function will panic if the number of powers is not a power of two.
+pub fn lagrange_basis<E: Engine>(g: &[E::G1Affine]) -> Vec<E::G1Affine> {
+    assert!(g.len().is_power_of_two());
+    let mut domain =
+        EvaluationDomain::from_coeffs(g.iter().map(|g| g.to_curve()).collect::<Vec<E::G1>>())
+            .expect("too many powers for the field's 2-adicity");
+    domain.ifft();
+
+    let g_lagrange = domain.into_coeffs();
+    let mut res = vec![E::G1Affine::identity(); g.len()];
+    E::G1::batch_normalize(&g_lagrange, &mut res);
+    res
+}
+
+/// Reads parameters in the layout of halo2's `ParamsKZG`: $k$ as a little
+/// endian `u32`, the $2^k$ powers $[s^i] G_1$, their Lagrange basis
+/// counterparts, $H$ and $[s] H$, all with compressed encodings. The
+/// Lagrange basis is checked to be well-formed but is not kept.
+///
+/// The powers are checked with `validation`, while $H$ and $[s] H$ are
+/// always checked to be in the prime order subgroup. $k$ must be at most
+/// the 2-adicity of the scalar field.
+pub fn read_halo2<E: Engine, R: io::Read>(
+    reader: &mut R,
+    validation: Validation,
+) -> io::Result<Params<E>> {
+    read_params(reader, validation, true)
+}
+
+/// Writes parameters in the layout of halo2's `ParamsKZG`, computing the
+/// Lagrange basis.
+pub fn write_halo2<E: Engine, W: io::Write>(params: &Params<E>, writer: &mut W) -> io::Result<()> {
+    write_params(params, writer, true)
+}
+
+/// Reads parameters in the layout of halo2's `ParamsKZG`, without the
+/// Lagrange basis unless `lagrange` is set.
+pub(crate) fn read_params<E: Engine, R: io::Read>(
+    reader: &mut R,
+    validation: Validation,
+    lagrange: bool,
+) -> io::Result<Params<E>> {
+    let mut k = [0u8; 4];
+    reader.read_exact(&mut k)?;
+    let k = u32::from_le_bytes(k);
+    // Larger domains have no evaluation domain in the scalar field
+    if k > E::Scalar::S || k >= usize::BITS - 1 {
+        return Err(invalid_data("invalid size of params"));
+    }
+    let n = 1usize << k;
+
+    let g = (0..n)
+        .map(|_| read_compressed(reader, validation))
+        .collect::<io::Result<Vec<E::G1Affine>>>()?;
+    if lagrange {
+        for _ in 0..n {
+            read_compressed::<E::G1Affine, _>(reader, validation)?;
+        }
+    }
+    // The pairing checks are only sound for these two in the subgroup, and
+    // there are too few of them for the check to matter
+    let g2 = read_compressed(reader, Validation::Subgroup)?;
+    let s_g2 = read_compressed(reader, Validation::Subgroup)?;
+
+    Ok(Params::from_parts(g, g2, s_g2))
+}
+
+/// Writes parameters in the layout of halo2's `ParamsKZG`, without the
+/// Lagrange basis unless `lagrange` is set.
+pub(crate) fn write_params<E: Engine, W: io::Write>(
+    params: &Params<E>,
+    writer: &mut W,
+    lagrange: bool,
+) -> io::Result<()> {
+    writer.write_all(&params.k().to_le_bytes())?;
+    for g in params.g() {
+        writer.write_all(g.to_bytes().as_ref())?;
+    }
+    if lagrange {
+        for g in lagrange_basis::<E>(params.g()) {
+            writer.write_all(g.to_bytes().as_ref())?;
+        }
+    }
+    writer.write_all(params.g2().to_bytes().as_ref())?;
+    writer.write_all(params.s_g2().to_bytes().as_ref())
+}
+
+fn read_compressed<C: CurveAffine, R: io::Read>(
+    reader: &mut R,
+    validation: Validation,
+) -> io::Result<C> {
+    let mut repr = C::Repr::default();
+    reader.read_exact(repr.as_mut())?;
+    let point = Option::from(C::from_bytes(&repr))
+        .ok_or_else(|| invalid_data("invalid point encoding in params"))?;
+    validation.check(point)
+}
+
+/// The contents of a `.ptau` file over BN254, as written by the powers of
+/// tau ceremony of snarkjs.
+///
+/// Contributions and the sections added by `snarkjs powersoftau
+/// prepare phase2` are skipped when reading.
+#[derive(Clone, Debug)]
+pub struct Ptau {
+    /// The logarithm of the number of powers in this file.
+    pub power: u32,
+    /// The logarithm of the number of powers of the whole ceremony.
+    pub ceremony_power: u32,
+    /// $[\tau^i] G_1$ for $i < 2^{power + 1} - 1$.
+    pub tau_g1: Vec<G1Affine>,
+    /// $[\tau^i] G_2$ for $i < 2^{power}$.
+    pub tau_g2: Vec<G2Affine>,
+    /// $[\alpha \tau^i] G_1$ for $i < 2^{power}$.
+    pub alpha_tau_g1: Vec<G1Affine>,
+    /// $[\beta \tau^i] G_1$ for $i < 2^{power}$.
+    pub beta_tau_g1: Vec<G1Affine>,
+    /// $[\beta] G_2$.
+    pub beta_g2: G2Affine,
+}
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const PTAU_VERSION: u32 = 1;
+const PTAU_N8: u32 = 32;
+
+const SECTION_HEADER: u32 = 1;
+const SECTION_TAU_G1: u32 = 2;
+const SECTION_TAU_G2: u32 = 3;
+const SECTION_ALPHA_TAU_G1: u32 = 4;
+const SECTION_BETA_TAU_G1: u32 = 5;
+const SECTION_BETA_G2: u32 = 6;
+const SECTION_CONTRIBUTIONS: u32 = 7;
+
+impl Ptau {
+    /// Reads a `.ptau` file, streaming the points of each section.
+    pub fn read<R: io::Read>(reader: &mut R, validation: Validation) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != PTAU_MAGIC {
+            return Err(invalid_data("not a ptau file"));
+        }
+        if read_u32(reader)? != PTAU_VERSION {
+            return Err(invalid_data("unsupported ptau version"));
+        }
+        let num_sections = read_u32(reader)?;
+
+        let mut header = None;
+        let mut tau_g1 = None;
+        let mut tau_g2 = None;
+        let mut alpha_tau_g1 = None;
+        let mut beta_tau_g1 = None;
+        let mut beta_g2 = None;
+        for _ in 0..num_sections {
+            let id = read_u32(reader)?;
+            let size = read_u64(reader)?;
+            let mut section = io::Read::take(&mut *reader, size);
+
+            if id == SECTION_HEADER {
+                header = Some(read_ptau_header(&mut section)?);
+            } else if (SECTION_TAU_G1..=SECTION_BETA_G2).contains(&id) {
+                let (power, _) = header.ok_or_else(|| invalid_data("missing ptau header"))?;
+                let n = 1usize << power;
+                let (count, point_size) = match id {
+                    SECTION_TAU_G1 => (2 * n - 1, 64),
+                    SECTION_TAU_G2 => (n, 128),
+                    SECTION_BETA_G2 => (1, 128),
+                    _ => (n, 64),
+                };
+                if size != (count * point_size) as u64 {
+                    return Err(invalid_data("invalid size of ptau section"));
+                }
+
+                match id {
+                    SECTION_TAU_G1 => {
+                        tau_g1 = Some(read_points(
+                            &mut section,
+                            count,
+                            validation,
+                            read_g1_montgomery,
+                        )?)
+                    }
+                    SECTION_TAU_G2 => {
+                        tau_g2 = Some(read_points(
+                            &mut section,
+                            count,
+                            validation,
+                            read_g2_montgomery,
+                        )?)
+                    }
+                    SECTION_ALPHA_TAU_G1 => {
+                        alpha_tau_g1 = Some(read_points(
+                            &mut section,
+                            count,
+                            validation,
+                            read_g1_montgomery,
+                        )?)
+                    }
+                    SECTION_BETA_TAU_G1 => {
+                        beta_tau_g1 = Some(read_points(
+                            &mut section,
+                            count,
+                            validation,
+                            read_g1_montgomery,
+                        )?)
+                    }
+                    _ => beta_g2 = Some(read_g2_montgomery(&mut section, validation)?),
+                }
+            }
+
+            // Skip the rest of the section, which is all of it for sections
+            // that are not needed
+            io::copy(&mut section, &mut io::sink())?;
+            if section.limit() != 0 {
+                return Err(invalid_data("truncated ptau section"));
+            }
+        }
+
+        let missing = || invalid_data("missing ptau section");
+        let (power, ceremony_power) = header.ok_or_else(missing)?;
+        Ok(Ptau {
+            power,
+            ceremony_power,
+            tau_g1: tau_g1.ok_or_else(missing)?,
+            tau_g2: tau_g2.ok_or_else(missing)?,
+            alpha_tau_g1: alpha_tau_g1.ok_or_else(missing)?,
+            beta_tau_g1: beta_tau_g1.ok_or_else(missing)?,
+            beta_g2: beta_g2.ok_or_else(missing)?,
+        })
+    }
+
+    /// Writes a `.ptau` file with the header, the five point sections and an
+    /// empty list of contributions.
+    pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(PTAU_MAGIC)?;
+        writer.write_all(&PTAU_VERSION.to_le_bytes())?;
+        writer.write_all(&7u32.to_le_bytes())?;
+
+        write_section_header(writer, SECTION_HEADER, 4 + PTAU_N8 as u64 + 8)?;
+        writer.write_all(&PTAU_N8.to_le_bytes())?;
+        writer.write_all(&modulus_bytes())?;
+        writer.write_all(&self.power.to_le_bytes())?;
+        writer.write_all(&self.ceremony_power.to_le_bytes())?;
+
+        write_section_header(writer, SECTION_TAU_G1, 64 * self.tau_g1.len() as u64)?;
+        for p in self.tau_g1.iter() {
+            write_g1_montgomery(writer, p)?;
+        }
+        write_section_header(writer, SECTION_TAU_G2, 128 * self.tau_g2.len() as u64)?;
+        for p in self.tau_g2.iter() {
+            write_g2_montgomery(writer, p)?;
+        }
+        write_section_header(
+            writer,
+            SECTION_ALPHA_TAU_G1,
+            64 * self.alpha_tau_g1.len() as u64,
+        )?;
+        for p in self.alpha_tau_g1.iter() {
+            write_g1_montgomery(writer, p)?;
+        }
+        write_section_header(
+            writer,
+            SECTION_BETA_TAU_G1,
+            64 * self.beta_tau_g1.len() as u64,
+        )?;
+        for p in self.beta_tau_g1.iter() {
+            write_g1_montgomery(writer, p)?;
+        }
+        write_section_header(writer, SECTION_BETA_G2, 128)?;
+        write_g2_montgomery(writer, &self.beta_g2)?;
+
+        write_section_header(writer, SECTION_CONTRIBUTIONS, 4)?;
+        writer.write_all(&0u32.to_le_bytes())
+    }
+
+    /// Checks that the points of the file are consistent powers of $\tau$,
+    /// $\alpha \tau$ and $\beta \tau$, and that $[\beta] G_2$ matches
+    /// $[\beta] G_1$. The equations are combined with random scalars sampled
+    /// from `rng`, so that a single final exponentiation is needed.
+    pub fn verify(&self, mut rng: impl RngCore) -> Choice {
+        let n = 1usize << self.power;
+        if self.tau_g1.len() != 2 * n - 1
+            || self.tau_g2.len() != n
+            || self.alpha_tau_g1.len() != n
+            || self.beta_tau_g1.len() != n
+            || n < 2
+        {
+            return Choice::from(0);
+        }
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        if self.tau_g1[0] != g1 || self.tau_g2[0] != g2 {
+            return Choice::from(0);
+        }
+
+        // e(sum_i r_i p_i, [tau] G_2) = e(sum_i r_i p_{i + 1}, G_2) for each
+        // sequence of powers p in G_1
+        let mut lhs = vec![];
+        let mut rhs = vec![];
+        for points in [&self.tau_g1, &self.alpha_tau_g1, &self.beta_tau_g1] {
+            let (l, r) = shifted_combination(points, &mut rng);
+            lhs.push(l);
+            rhs.push(r);
+        }
+        let lhs = (lhs[0] + lhs[1] + lhs[2]).to_affine();
+
+        // e([beta] G_1, G_2) = e(G_1, [beta] G_2), folded into the check
+        // against G_2
+        let u = Fr::random(&mut rng);
+        let rhs = (-(rhs[0] + rhs[1] + rhs[2]) + self.beta_tau_g1[0] * u).to_affine();
+        let neg_u_g1 = (g1 * -u).to_affine();
+
+        // e([tau] G_1, sum_i t_i q_i) = e(G_1, sum_i t_i q_{i + 1}) for the
+        // powers q in G_2
+        let (l2, r2) = shifted_combination(&self.tau_g2, &mut rng);
+        let neg_g1 = -g1;
+
+        Bn256::pairing_check(&[
+            (&lhs, &G2Prepared::from(self.tau_g2[1])),
+            (&rhs, &G2Prepared::from(g2)),
+            (&neg_u_g1, &G2Prepared::from(self.beta_g2)),
+            (&self.tau_g1[1], &G2Prepared::from(l2)),
+            (&neg_g1, &G2Prepared::from(r2)),
+        ])
+    }
+
+    /// Returns the KZG parameters for polynomials of degree less than $2^k$.
+    ///
+    /// This function will panic if `k` is larger than the power of the file.
+    pub fn params(&self, k: u32) -> Params<Bn256> {
+        assert!(k <= self.power);
+        Params::from_parts(
+            self.tau_g1[..1 << k].to_vec(),
+            self.tau_g2[0],
+            self.tau_g2[1],
+        )
+    }
+}
+
+/// Returns the little endian bytes of the modulus of `Fq`.
+fn modulus_bytes() -> [u8; 32] {
+    // The modulus is odd, so adding one to q - 1 does not carry
+    let mut bytes = (-Fq::one()).to_bytes();
+    bytes[0] += 1;
+    bytes
+}
+
+fn read_ptau_header<R: io::Read>(reader: &mut R) -> io::Result<(u32, u32)> {
+    if read_u32(reader)? != PTAU_N8 {
+        return Err(invalid_data("unsupported ptau field size"));
+    }
+    let mut modulus = [0u8; 32];
+    reader.read_exact(&mut modulus)?;
+    if modulus != modulus_bytes() {
+        return Err(invalid_data("ptau file is not over BN254"));
+    }
+    let power = read_u32(reader)?;
+    let ceremony_power = read_u32(reader)?;
+    // A ceremony has at least two powers of tau, and the 2-adicity of Fr
+    // bounds the size of any ceremony over BN254
+    if power == 0 || power > 28 {
+        return Err(invalid_data("invalid ptau power"));
+    }
+    Ok((power, ceremony_power))
+}
+
+fn write_section_header<W: io::Write>(writer: &mut W, id: u32, size: u64) -> io::Result<()> {
+    writer.write_all(&id.to_le_bytes())?;
+    writer.write_all(&size.to_le_bytes())
+}
+
+fn read_points<R: io::Read, T>(
+    reader: &mut R,
+    count: usize,
+    validation: Validation,
+    read: impl Fn(&mut R, Validation) -> io::Result<T>,
+) -> io::Result<Vec<T>> {
+    (0..count).map(|_| read(reader, validation)).collect()
+}
+
+fn read_u32<R: io::Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: io::Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads an element of `Fq` in Montgomery form with little endian limbs,
+/// the encoding used by snarkjs.
+fn read_fq_montgomery<R: io::Read>(reader: &mut R) -> io::Result<Fq> {
+    let mut bytes = [0u8; 32];
+    reader.read_exact(&mut bytes)?;
+    // The Montgomery form is the internal representation, and must be
+    // reduced like a canonical encoding
+    if bool::from(Fq::from_bytes(&bytes).is_none()) {
+        return Err(invalid_data("invalid field element in ptau"));
+    }
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    Ok(Fq(limbs))
+}
+
+fn write_fq_montgomery<W: io::Write>(writer: &mut W, x: &Fq) -> io::Result<()> {
+    for limb in x.0.iter() {
+        writer.write_all(&limb.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_fq2_montgomery<R: io::Read>(reader: &mut R) -> io::Result<Fq2> {
+    let c0 = read_fq_montgomery(reader)?;
+    let c1 = read_fq_montgomery(reader)?;
+    Ok(Fq2 { c0, c1 })
+}
+
+/// Reads a point with coordinates in Montgomery form, where the identity is
+/// encoded as zeros like in this crate.
+fn read_g1_montgomery<R: io::Read>(reader: &mut R, validation: Validation) -> io::Result<G1Affine> {
+    let x = read_fq_montgomery(reader)?;
+    let y = read_fq_montgomery(reader)?;
+    let point = Option::from(G1Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("point is not on the curve"))?;
+    validation.check(point)
+}
+
+fn read_g2_montgomery<R: io::Read>(reader: &mut R, validation: Validation) -> io::Result<G2Affine> {
+    let x = read_fq2_montgomery(reader)?;
+    let y = read_fq2_montgomery(reader)?;
+    let point = Option::from(G2Affine::from_xy(x, y))
+        .ok_or_else(|| invalid_data("point is not on the curve"))?;
+    validation.check(point)
+}
+
+fn write_g1_montgomery<W: io::Write>(writer: &mut W, p: &G1Affine) -> io::Result<()> {
+    write_fq_montgomery(writer, &p.x)?;
+    write_fq_montgomery(writer, &p.y)
+}
+
+fn write_g2_montgomery<W: io::Write>(writer: &mut W, p: &G2Affine) -> io::Result<()> {
+    for x in [&p.x.c0, &p.x.c1, &p.y.c0, &p.y.c1] {
+        write_fq_montgomery(writer, x)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+use crate::arithmetic::{LagrangeCoeff, Polynomial};
+#[cfg(test)]
+use crate::bn256::G1;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
+
+#[cfg(test)]
+fn unsafe_ptau(power: u32, mut rng: impl RngCore) -> Ptau {
+    let (tau, alpha, beta) = (
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+        Fr::random(&mut rng),
+    );
+    let powers: Vec<_> = std::iter::successors(Some(Fr::one()), |x| Some(*x * tau))
+        .take(2 << power)
+        .collect();
+    let n = 1 << power;
+    let g1 = |scale: Fr, count: usize| {
+        powers[..count]
+            .iter()
+            .map(|x| (G1Affine::generator() * (scale * x)).to_affine())
+            .collect::<Vec<_>>()
+    };
+
+    Ptau {
+        power,
+        ceremony_power: power,
+        tau_g1: g1(Fr::one(), 2 * n - 1),
+        tau_g2: powers[..n]
+            .iter()
+            .map(|x| (G2Affine::generator() * x).to_affine())
+            .collect(),
+        alpha_tau_g1: g1(alpha, n),
+        beta_tau_g1: g1(beta, n),
+        beta_g2: (G2Affine::generator() * beta).to_affine(),
+    }
+}
+
+#[test]
+fn test_halo2_layout() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let params = Params::<Bn256>::unsafe_setup(4, &mut rng);
+    assert!(bool::from(check_powers::<Bn256>(
+        params.g(),
+        params.g2(),
+        params.s_g2(),
+        &mut rng
+    )));
+
+    let mut bytes = vec![];
+    write_halo2(&params, &mut bytes).unwrap();
+    assert_eq!(bytes.len(), 4 + 2 * 16 * 32 + 2 * 64);
+    for validation in [Validation::OnCurve, Validation::Subgroup] {
+        let read = read_halo2::<Bn256, _>(&mut &bytes[..], validation).unwrap();
+        assert_eq!(read.g(), params.g());
+        assert_eq!(read.g2(), params.g2());
+        assert_eq!(read.s_g2(), params.s_g2());
+    }
+    assert!(read_halo2::<Bn256, _>(&mut &bytes[..bytes.len() - 1], Validation::OnCurve).is_err());
+
+    // Committing to evaluations with the Lagrange basis agrees with
+    // committing to the coefficients
+    let evals: Vec<_> = (0..16).map(|_| Fr::random(&mut rng)).collect();
+    let g_lagrange = lagrange_basis::<Bn256>(params.g());
+    let poly = Polynomial::<Fr, LagrangeCoeff>::from_evals(evals.clone()).into_coeffs();
+    assert_eq!(
+        best_multiexp(&evals, &g_lagrange).to_affine(),
+        params.commit(&poly)
+    );
+
+    // Powers of a different secret are rejected
+    let mut g = params.g().to_vec();
+    g[7] = (g[7] * Fr::from(2)).to_affine();
+    assert!(!bool::from(check_powers::<Bn256>(
+        &g,
+        params.g2(),
+        params.s_g2(),
+        &mut rng
+    )));
+}
+
+#[test]
+fn test_ptau() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let ptau = unsafe_ptau(3, &mut rng);
+    assert!(bool::from(ptau.verify(&mut rng)));
+
+    let mut bytes = vec![];
+    ptau.write(&mut bytes).unwrap();
+    let read = Ptau::read(&mut &bytes[..], Validation::Subgroup).unwrap();
+    assert_eq!(read.power, 3);
+    assert_eq!(read.tau_g1, ptau.tau_g1);
+    assert_eq!(read.tau_g2, ptau.tau_g2);
+    assert_eq!(read.alpha_tau_g1, ptau.alpha_tau_g1);
+    assert_eq!(read.beta_tau_g1, ptau.beta_tau_g1);
+    assert_eq!(read.beta_g2, ptau.beta_g2);
+
+    let params = read.params(2);
+    assert_eq!(params.g(), &ptau.tau_g1[..4]);
+    assert_eq!(params.s_g2(), &ptau.tau_g2[1]);
+
+    // Truncated and foreign files are rejected
+    assert!(Ptau::read(&mut &bytes[..bytes.len() - 1], Validation::OnCurve).is_err());
+    let mut foreign = bytes.clone();
+    foreign[0] = b'x';
+    assert!(Ptau::read(&mut &foreign[..], Validation::OnCurve).is_err());
+
+    // Headers with a power out of range are rejected
+    for power in [0u32, 29] {
+        let mut header = vec![];
+        header.extend_from_slice(&PTAU_N8.to_le_bytes());
+        header.extend_from_slice(&modulus_bytes());
+        header.extend_from_slice(&power.to_le_bytes());
+        header.extend_from_slice(&power.to_le_bytes());
+        assert!(read_ptau_header(&mut &header[..]).is_err());
+    }
+
+    // Every kind of inconsistency is caught
+    let mut invalid = ptau.clone();
+    invalid.tau_g1[5] = (G1::from(invalid.tau_g1[5]) + G1::from(invalid.tau_g1[1])).to_affine();
+    assert!(!bool::from(invalid.verify(&mut rng)));
+    let mut invalid = ptau.clone();
+    invalid.tau_g2[3] = (invalid.tau_g2[3] * Fr::from(3)).to_affine();
+    assert!(!bool::from(invalid.verify(&mut rng)));
+    let mut invalid = ptau.clone();
+    invalid.alpha_tau_g1[2] = (invalid.alpha_tau_g1[2] * Fr::from(3)).to_affine();
+    assert!(!bool::from(invalid.verify(&mut rng)));
+    let mut invalid = ptau.clone();
+    invalid.beta_g2 = (invalid.beta_g2 * Fr::from(3)).to_affine();
+    assert!(!bool::from(invalid.verify(&mut rng)));
+}
@@ -210,16 +210,19 @@ fn random_expansion_tests<F: Field, R: RngCore>(mut rng: R, type_name: String) {
 
 use crate::arithmetic::BaseExt;
 use crate::arithmetic::MillerLoopResult;
+use crate::arithmetic::MillerLoopStrategies;
+use crate::arithmetic::MillerLoopStrategy;
 use crate::bn256;
+use crate::bn256::Bn256;
 use crate::bn256::Fq;
 use crate::bn256::Fq12;
 use crate::bn256::Gt;
+use crate::bn256::NthRootExponents;
 use crate::group::Curve;
 use crate::group::Group;
 use ark_std::One;
 use num_bigint::BigUint;
 use num_traits::Num;
-use num_traits::ToPrimitive;
 use rand_core::OsRng;
 use std::str::FromStr;
 
@@ -269,17 +272,17 @@ fn test_pairing_with_c_wi() {
     let f = bn256::multi_miller_loop(&[(&p1.neg().to_affine(), &q1_prepared), (&p2, &q2_prepared)]);
     println!("Bn254::multi_miller_loop done!");
     let (c, wi) = compute_c_wi(f);
-    let c_inv = c.invert().unwrap();
+    let c_inv = Gt(c.0.invert().unwrap());
     let hint = if sign {
-        f * wi * (c_inv.pow_vartime(exp.to_u64_digits()))
+        f * wi * Gt(c_inv.0.pow_vartime(exp.to_u64_digits()))
     } else {
-        f * wi * (c_inv.pow_vartime(exp.to_u64_digits()).invert().unwrap())
+        f * wi * Gt(c_inv.0.pow_vartime(exp.to_u64_digits()).invert().unwrap())
     };
 
     //6x+2
     // let six_x_2 = BigUint::from_str("29793968203157093288").unwrap();
     // println!("c_lamada-p3={:?}", c_inv.pow_vartime(six_x_2.to_u64_digits()));
-    assert_eq!(hint, c.pow_vartime(p_pow3.to_u64_digits()));
+    assert_eq!(hint, Gt(c.0.pow_vartime(p_pow3.to_u64_digits())));
 
     assert_eq!(
         Fq12::one(),
@@ -338,17 +341,17 @@ fn test_on_prove_paring() {
     let f = bn256::multi_miller_loop(&[(&p1.neg().to_affine(), &q1_prepared), (&p2, &q2_prepared)]);
     println!("Bn254::multi_miller_loop done!");
     let (c, wi) = compute_c_wi(f);
-    let c_inv = c.invert().unwrap();
+    let c_inv = Gt(c.0.invert().unwrap());
     let hint = if sign {
-        f * wi * (c_inv.pow_vartime(exp.to_u64_digits()))
+        f * wi * Gt(c_inv.0.pow_vartime(exp.to_u64_digits()))
     } else {
-        f * wi * (c_inv.pow_vartime(exp.to_u64_digits()).invert().unwrap())
+        f * wi * Gt(c_inv.0.pow_vartime(exp.to_u64_digits()).invert().unwrap())
     };
 
     //6x+2
     // let six_x_2 = BigUint::from_str("29793968203157093288").unwrap();
     // println!("c_lamada-p3={:?}", c_inv.pow_vartime(six_x_2.to_u64_digits()));
-    assert_eq!(hint, c.pow_vartime(p_pow3.to_u64_digits()));
+    assert_eq!(hint, Gt(c.0.pow_vartime(p_pow3.to_u64_digits())));
 
     assert_eq!(
         Fq12::one(),
@@ -376,13 +379,13 @@ fn test_on_prove_paring() {
     ]);
     println!("on prove pairing calc miller f!");
     let (c, wi) = compute_c_wi(f);
-    let c_inv = c.invert().unwrap();
+    let c_inv = Gt(c.0.invert().unwrap());
     let hint = if sign {
-        f * wi * (c_inv.pow_vartime(exp.to_u64_digits()))
+        f * wi * Gt(c_inv.0.pow_vartime(exp.to_u64_digits()))
     } else {
-        f * wi * (c_inv.pow_vartime(exp.to_u64_digits()).invert().unwrap())
+        f * wi * Gt(c_inv.0.pow_vartime(exp.to_u64_digits()).invert().unwrap())
     };
-    assert_eq!(hint, c.pow_vartime(p_pow3.to_u64_digits()));
+    assert_eq!(hint, Gt(c.0.pow_vartime(p_pow3.to_u64_digits())));
 
     assert_eq!(
         Fq12::one(),
@@ -400,46 +403,65 @@ fn test_on_prove_paring() {
     println!("Accumulated f_c_wi done!");
 }
 
-// refer https://github.com/BitVM/BitVM/blob/main/src/fflonk/compute_c_wi.rs
-// refer table 3 of https://eprint.iacr.org/2009/457.pdf
-// a: Fp12 which is cubic residue
-// c: random Fp12 which is cubic non-residue
-// s: satisfying p^12 - 1 = 3^s * t
-// t: satisfying p^12 - 1 = 3^s * t
-// k: k = (t + 1) // 3
-fn tonelli_shanks_cubic(a: Fq12, c: Fq12, s: u32, t: BigUint, k: BigUint) -> Gt {
-    let mut r = a.pow_vartime(t.to_u64_digits());
-    let e = 3_u32.pow(s - 1);
-    let exp = 3_u32.pow(s) * &t;
-
-    // compute cubic root of (a^t)^-1, say h
-    let (mut h, cc, mut c) = (Fq12::one(), c.pow_vartime([e as u64]), c.invert().unwrap());
-    for i in 1..(s as i32) {
-        let delta = (s as i32) - i - 1;
-        let d = if delta < 0 {
-            r.pow_vartime((&exp / 3_u32.pow((-delta) as u32)).to_u64_digits())
-        } else {
-            r.pow_vartime([3_u32.pow(delta as u32).to_u64().unwrap()])
-        };
-        if d == cc {
-            (h, r) = (h * c, r * c.pow_vartime([3_u64]));
-        } else if d == cc.pow_vartime([2_u64]) {
-            (h, r) = (
-                h * c.pow_vartime([2_u64]),
-                r * c.pow_vartime([3_u64]).pow_vartime([2_u64]),
-            );
-        }
-        c = c.pow_vartime([3_u64])
-    }
+// Checks the c/wi verifier of a strategy on e(-P1, Q1) * e(P2, Q2) = 1,
+// with the right witness and a wrong one
+fn check_miller_loop_strategy<S: MillerLoopStrategy<Bn256>>() {
+    let p1 = bn256::G1::random(&mut OsRng);
+    let q2 = bn256::G2::random(&mut OsRng);
+    let factor = bn256::Fr::from_raw([3_u64, 0, 0, 0]);
+    let p1_neg = p1.neg().to_affine();
+    let p2 = p1.mul(&factor).to_affine();
+    let q1_prepared = S::G2Prepared::from(q2.mul(&factor).to_affine());
+    let q2_prepared = S::G2Prepared::from(q2.to_affine());
+    let terms = [(&p1_neg, &q1_prepared), (&p2, &q2_prepared)];
+
+    assert!(bool::from(S::pairing_check(&terms)));
+    let (c, wi) = compute_c_wi(S::multi_miller_loop(&terms));
+    assert!(bool::from(S::pairing_check_c_wi(&c, &wi, &terms)));
+    assert!(!bool::from(S::pairing_check_c_wi(&c, &c, &terms)));
+    assert!(!bool::from(S::pairing_check_c_wi(&wi, &wi, &terms)));
+    // A c without inverse is rejected rather than panicking
+    assert!(!bool::from(S::pairing_check_c_wi(
+        &Gt(Fq12::zero()),
+        &wi,
+        &terms
+    )));
+
+    // Terms with an identity on either side are skipped
+    let identity = bn256::G1::identity().to_affine();
+    let q0_prepared = S::G2Prepared::from(bn256::G2::random(&mut OsRng).to_affine());
+    let zero_prepared = S::G2Prepared::from(bn256::G2::identity().to_affine());
+    let terms = [
+        (&identity, &q0_prepared),
+        (&p1_neg, &q1_prepared),
+        (&p2, &zero_prepared),
+        (&p2, &q2_prepared),
+    ];
+    assert!(bool::from(S::pairing_check(&terms)));
+    let (c, wi) = compute_c_wi(S::multi_miller_loop(&terms));
+    assert!(bool::from(S::pairing_check_c_wi(&c, &wi, &terms)));
+    assert!(!bool::from(S::pairing_check_c_wi(&c, &c, &terms)));
+}
 
-    // recover cubic root of a
-    r = a.pow_vartime(k.to_u64_digits()) * h;
-    if t == 3_u32 * k + 1_u32 {
-        r = r.invert().unwrap();
-    }
+#[test]
+fn test_miller_loop_strategies() {
+    type Projective = <Bn256 as MillerLoopStrategies>::ProjectiveStrategy;
+    type Affine = <Bn256 as MillerLoopStrategies>::AffineStrategy;
+
+    // The two strategies agree after the final exponentiation
+    let p = bn256::G1::random(&mut OsRng).to_affine();
+    let q = bn256::G2::random(&mut OsRng).to_affine();
+    let projective = Projective::multi_miller_loop(&[(&p, &bn256::G2Prepared::from(q))]);
+    let affine = Affine::multi_miller_loop(&[(&p, &bn256::G2OnProvePrepared::from(q))]);
+    assert_ne!(projective, affine);
+    assert_eq!(
+        projective.final_exponentiation(),
+        affine.final_exponentiation()
+    );
+    assert_eq!(projective.final_exponentiation(), bn256::pairing(&p, &q));
 
-    assert_eq!(r.pow_vartime([3_u64]), a);
-    Gt(r)
+    check_miller_loop_strategy::<Projective>();
+    check_miller_loop_strategy::<Affine>();
 }
 
 // refer from Algorithm 5 of "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
@@ -462,28 +484,24 @@ fn compute_c_wi(f: Gt) -> (Gt, Gt) {
     let exp = p.pow(12_u32) - 1_u32;
     let h = &exp / &r;
     let t = &exp / 3_u32.pow(s);
-    let k = (&t + 1_u32) / 3_u32;
     let m = &lambda / &r;
     let d = 3_u32;
     let mm = &m / d;
 
     // let mut prng = ChaCha20Rng::seed_from_u64(0);
-    let cofactor_cubic = 3_u32.pow(s - 1) * &t;
 
     // make f is r-th residue, but it's not cubic residue
-    assert_eq!(f.pow_vartime(h.to_u64_digits()).0, Fq12::one());
+    assert_eq!(f.0.pow_vartime(h.to_u64_digits()), Fq12::one());
     //todo sometimes  f is cubic residue
-    // assert_ne!(f.pow_vartime(cofactor_cubic.to_u64_digits()), Fq12::one());
+    // assert!(!bool::from(f.0.is_cubic_residue()));
 
     // sample a proper scalar w which is cubic non-residue
     let w = {
         let (mut w, mut z) = (Fq12::one(), Fq12::one());
         while w == Fq12::one() {
             // choose z which is 3-th non-residue
-            let mut legendre = Fq12::one();
-            while legendre == Fq12::one() {
+            while bool::from(z.is_cubic_residue()) {
                 z = Fq12::random(&mut OsRng);
-                legendre = z.pow_vartime(cofactor_cubic.to_u64_digits());
             }
             // obtain w which is t-th power of z
             w = z.pow_vartime(t.to_u64_digits());
@@ -491,51 +509,43 @@ fn compute_c_wi(f: Gt) -> (Gt, Gt) {
         Gt(w)
     };
     // make sure 27-th root w, is 3-th non-residue and r-th residue
-    assert_ne!(w.pow_vartime(cofactor_cubic.to_u64_digits()).0, Fq12::one());
-    assert_eq!(w.pow_vartime(h.to_u64_digits()).0, Fq12::one());
+    assert!(!bool::from(w.0.is_cubic_residue()));
+    assert_eq!(w.0.pow_vartime(h.to_u64_digits()), Fq12::one());
 
-    let wi = if f.pow_vartime(cofactor_cubic.to_u64_digits()).0 == Fq12::one() {
+    let wi = if bool::from(f.0.is_cubic_residue()) {
         println!("f is fq12_one------------");
         Gt(Fq12::one())
     } else {
         // just two option, w and w^2, since w^3 must be cubic residue, leading f*w^3 must not be cubic residue
         let mut wi = w;
-        if (f * wi).pow_vartime(cofactor_cubic.to_u64_digits()).0 != Fq12::one() {
-            assert_eq!(
-                (f * w * w).pow_vartime(cofactor_cubic.to_u64_digits()).0,
-                Fq12::one()
-            );
+        if !bool::from((f * wi).0.is_cubic_residue()) {
+            assert!(bool::from((f * w * w).0.is_cubic_residue()));
             wi = w * w;
         }
         wi
     };
 
-    assert_eq!(wi.pow_vartime(h.to_u64_digits()).0, Fq12::one());
+    assert_eq!(wi.0.pow_vartime(h.to_u64_digits()), Fq12::one());
 
     assert_eq!(lambda, &d * &mm * &r);
     // f1 is scaled f
     let f1 = f * wi;
 
     // r-th root of f1, say f2
-    let r_inv = r.modinv(&h).unwrap();
-    assert_ne!(r_inv, BigUint::one());
-    let f2 = f1.pow_vartime(r_inv.to_u64_digits());
+    let r_exponents = NthRootExponents::new(r.to_u64_digits());
+    let f2 = Gt(f1.0.nth_root(&r_exponents).unwrap());
     assert_ne!(f2.0, Fq12::one());
 
     // m'-th root of f, say f3
-    let mm_inv = mm.modinv(&(r * h)).unwrap();
-    assert_ne!(mm_inv, BigUint::one());
-    let f3 = f2.pow_vartime(mm_inv.to_u64_digits());
-    assert_eq!(
-        f3.pow_vartime(cofactor_cubic.to_u64_digits()).0,
-        Fq12::one()
-    );
+    let mm_exponents = NthRootExponents::new(mm.to_u64_digits());
+    let f3 = Gt(f2.0.nth_root(&mm_exponents).unwrap());
+    assert!(bool::from(f3.0.is_cubic_residue()));
     assert_ne!(f3.0, Fq12::one());
 
     // d-th (cubic) root, say c
-    let c = tonelli_shanks_cubic(f3.0, w.0, s, t, k);
+    let c = Gt(f3.0.cube_root().unwrap());
     assert_ne!(c.0, Fq12::one());
-    assert_eq!(c.pow_vartime(lambda.to_u64_digits()), f * wi);
+    assert_eq!(Gt(c.0.pow_vartime(lambda.to_u64_digits())), f * wi);
 
     (c, wi)
 }
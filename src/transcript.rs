@@ -0,0 +1,548 @@
+//! This module implements Fiat-Shamir transcripts over BN254, which absorb
+//! the messages of a prover and squeeze `Fr` challenges from them.
+//!
+//! Every value is absorbed with a canonical encoding:
+//!
+//! - field elements as their normalized little endian bytes, with the
+//!   coefficients of extension fields in order, so that an `Fq12` is the
+//!   384 bytes of `Fq12::to_bytes` and a `Gt` the ones of its `Fq12`;
+//! - points as a flag byte, $1$ for the identity and $0$ otherwise, followed
+//!   by the encodings of $x$ and $y$, which are zero for the identity.
+//!
+//! Each encoding is absorbed with the tag of its [`Kind`], and challenges
+//! with a tag of their own, so that values of different kinds never encode
+//! the same input to the hash.
+//!
+//! The Blake2b and Keccak transcripts hash the absorbed bytes and reduce 64
+//! bytes of output into a challenge with `from_bytes_wide`. The Poseidon
+//! transcript absorbs scalars natively into a sponge over `Fr`, using the
+//! permutation of circomlib with width 3.
+
+use crate::arithmetic::{BaseExt, CurveAffine};
+use crate::bn256::{Fq12, Fr, Gt};
+use ff::{Field, PrimeField};
+use sha3::{Digest, Keccak256};
+use std::convert::TryInto;
+use std::sync::OnceLock;
+
+/// The kinds of values absorbed by a transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A byte string.
+    Bytes = 1,
+    /// An element of `Fr`.
+    Scalar = 2,
+    /// An element of a field implementing `BaseExt`.
+    Field = 3,
+    /// An element of `Fq12`.
+    Fq12 = 4,
+    /// An element of the target group.
+    Gt = 5,
+    /// A point of a curve.
+    Point = 6,
+}
+
+/// The tag of challenges, distinct from the tags of the kinds.
+const TAG_CHALLENGE: u8 = 7;
+
+/// A Fiat-Shamir transcript.
+pub trait Transcript {
+    /// Absorbs the encoding `bytes` of a value of the given kind. Sequences
+    /// of absorbed encodings are encoded unambiguously, even when their
+    /// kinds and lengths vary.
+    fn absorb_encoding(&mut self, kind: Kind, bytes: &[u8]);
+
+    /// Squeezes a challenge out of everything absorbed so far. Consecutive
+    /// challenges are distinct.
+    fn squeeze_challenge(&mut self) -> Fr;
+
+    /// Absorbs `bytes`.
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb_encoding(Kind::Bytes, bytes);
+    }
+
+    /// Absorbs a scalar.
+    fn absorb_scalar(&mut self, scalar: &Fr) {
+        self.absorb_encoding(Kind::Scalar, &scalar.to_repr());
+    }
+
+    /// Absorbs an element of any field, such as `Fq` or `Fq2`.
+    fn absorb_field<F: BaseExt>(&mut self, element: &F) {
+        let mut bytes = vec![];
+        element.write(&mut bytes).unwrap();
+        self.absorb_encoding(Kind::Field, &bytes);
+    }
+
+    /// Absorbs an element of `Fq12`.
+    fn absorb_fq12(&mut self, element: &Fq12) {
+        self.absorb_encoding(Kind::Fq12, &element.to_bytes());
+    }
+
+    /// Absorbs an element of the target group.
+    fn absorb_gt(&mut self, element: &Gt) {
+        self.absorb_encoding(Kind::Gt, &element.0.to_bytes());
+    }
+
+    /// Absorbs a point, flagging the identity, whose coordinates are not
+    /// defined.
+    fn absorb_point<C: CurveAffine>(&mut self, point: &C) {
+        let (flag, x, y) = if bool::from(point.is_identity()) {
+            (1u8, C::Base::zero(), C::Base::zero())
+        } else {
+            let coordinates = point.coordinates().unwrap();
+            (0u8, *coordinates.x(), *coordinates.y())
+        };
+
+        let mut bytes = vec![flag];
+        x.write(&mut bytes).unwrap();
+        y.write(&mut bytes).unwrap();
+        self.absorb_encoding(Kind::Point, &bytes);
+    }
+}
+
+/// A transcript hashing with Blake2b.
+#[derive(Clone)]
+pub struct Blake2bTranscript {
+    state: blake2b_simd::State,
+}
+
+impl Blake2bTranscript {
+    /// Creates a transcript for the protocol identified by `label`.
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Blake2bTranscript {
+            state: blake2b_simd::Params::new().hash_length(64).to_state(),
+        };
+        transcript.absorb_bytes(label);
+        transcript
+    }
+}
+
+impl Transcript for Blake2bTranscript {
+    fn absorb_encoding(&mut self, kind: Kind, bytes: &[u8]) {
+        self.state.update(&[kind as u8]);
+        self.state.update(&(bytes.len() as u64).to_le_bytes());
+        self.state.update(bytes);
+    }
+
+    fn squeeze_challenge(&mut self) -> Fr {
+        self.state.update(&[TAG_CHALLENGE]);
+        let hash = self.state.finalize();
+        Fr::from_bytes_wide(hash.as_bytes().try_into().unwrap())
+    }
+}
+
+/// A transcript hashing with Keccak-256, as the EVM does.
+#[derive(Clone)]
+pub struct KeccakTranscript {
+    state: Keccak256,
+}
+
+impl KeccakTranscript {
+    /// Creates a transcript for the protocol identified by `label`.
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = KeccakTranscript {
+            state: Keccak256::new(),
+        };
+        transcript.absorb_bytes(label);
+        transcript
+    }
+}
+
+impl Transcript for KeccakTranscript {
+    fn absorb_encoding(&mut self, kind: Kind, bytes: &[u8]) {
+        self.state.update([kind as u8]);
+        self.state.update((bytes.len() as u64).to_le_bytes());
+        self.state.update(bytes);
+    }
+
+    fn squeeze_challenge(&mut self) -> Fr {
+        self.state.update([TAG_CHALLENGE]);
+        // Two hashes of the state give the 64 bytes to reduce
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.state.clone().chain_update([0u8]).finalize());
+        bytes[32..].copy_from_slice(&self.state.clone().chain_update([1u8]).finalize());
+        Fr::from_bytes_wide(&bytes)
+    }
+}
+
+/// The width of the Poseidon permutation.
+const WIDTH: usize = 3;
+/// The number of full rounds of the Poseidon permutation.
+const FULL_ROUNDS: usize = 8;
+/// The number of partial rounds of the Poseidon permutation.
+const PARTIAL_ROUNDS: usize = 57;
+
+/// The Grain LFSR that generates the constants of Poseidon, following the
+/// reference implementation of the paper, https://eprint.iacr.org/2019/458.pdf
+struct Grain {
+    bits: [bool; 80],
+    head: usize,
+}
+
+impl Grain {
+    fn new() -> Self {
+        let fields: [(u64, usize); 6] = [
+            // Prime field, x^alpha S-box
+            (1, 2),
+            (0, 4),
+            (Fr::NUM_BITS as u64, 12),
+            (WIDTH as u64, 12),
+            (FULL_ROUNDS as u64, 10),
+            (PARTIAL_ROUNDS as u64, 10),
+        ];
+        let mut bits = [true; 80];
+        let mut i = 0;
+        for (value, len) in fields {
+            for j in (0..len).rev() {
+                bits[i] = (value >> j) & 1 == 1;
+                i += 1;
+            }
+        }
+
+        let mut grain = Grain { bits, head: 0 };
+        for _ in 0..160 {
+            grain.update();
+        }
+        grain
+    }
+
+    fn update(&mut self) -> bool {
+        let bit = |i: usize| self.bits[(self.head + i) % 80];
+        let new_bit = bit(62) ^ bit(51) ^ bit(38) ^ bit(23) ^ bit(13) ^ bit(0);
+        self.bits[self.head] = new_bit;
+        self.head = (self.head + 1) % 80;
+        new_bit
+    }
+
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.update();
+            let bit = self.update();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Returns the next `Fr::NUM_BITS` bits as a big endian integer, in
+    /// little endian bytes.
+    fn next_bytes(&mut self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for i in (0..Fr::NUM_BITS as usize).rev() {
+            if self.next_bit() {
+                bytes[i / 8] |= 1 << (i % 8);
+            }
+        }
+        bytes
+    }
+
+    /// Returns the next field element, rejecting integers that are not
+    /// reduced.
+    fn next_field_element(&mut self) -> Fr {
+        loop {
+            if let Some(element) = Option::from(Fr::from_repr(self.next_bytes())) {
+                return element;
+            }
+        }
+    }
+
+    /// Returns the next integer reduced modulo $r$.
+    fn next_field_element_reduced(&mut self) -> Fr {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.next_bytes());
+        Fr::from_bytes_wide(&bytes)
+    }
+}
+
+/// The Poseidon permutation over `Fr` with width 3, $x^5$ S-boxes, 8 full
+/// and 57 partial rounds, and the constants generated as in the reference
+/// implementation. These are the parameters of circomlib.
+#[derive(Clone, Debug)]
+pub struct Poseidon {
+    round_constants: Vec<[Fr; WIDTH]>,
+    mds: [[Fr; WIDTH]; WIDTH],
+}
+
+impl Default for Poseidon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Poseidon {
+    /// Generates the round constants and the MDS matrix.
+    pub fn new() -> Self {
+        let mut grain = Grain::new();
+
+        let round_constants = (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+            .map(|_| {
+                [
+                    grain.next_field_element(),
+                    grain.next_field_element(),
+                    grain.next_field_element(),
+                ]
+            })
+            .collect();
+
+        // The Cauchy matrix 1 / (x_i + y_j) of 2t distinct elements
+        let mds = loop {
+            let xy: Vec<_> = (0..2 * WIDTH)
+                .map(|_| grain.next_field_element_reduced())
+                .collect();
+            let distinct = xy
+                .iter()
+                .enumerate()
+                .all(|(i, a)| xy[..i].iter().all(|b| a != b));
+            if !distinct {
+                continue;
+            }
+
+            let mut mds = [[Fr::zero(); WIDTH]; WIDTH];
+            let mut invertible = true;
+            for (row, x) in mds.iter_mut().zip(xy[..WIDTH].iter()) {
+                for (entry, y) in row.iter_mut().zip(xy[WIDTH..].iter()) {
+                    let inverse = (*x + y).invert();
+                    invertible &= bool::from(inverse.is_some());
+                    *entry = inverse.unwrap_or(Fr::zero());
+                }
+            }
+            if invertible {
+                break mds;
+            }
+        };
+
+        Poseidon {
+            round_constants,
+            mds,
+        }
+    }
+
+    /// Applies the permutation to `state`.
+    pub fn permute(&self, state: &mut [Fr; WIDTH]) {
+        let sbox = |x: Fr| x.square().square() * x;
+        let partial = FULL_ROUNDS / 2..FULL_ROUNDS / 2 + PARTIAL_ROUNDS;
+
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants.iter()) {
+                *s += c;
+            }
+            if partial.contains(&round) {
+                state[0] = sbox(state[0]);
+            } else {
+                for s in state.iter_mut() {
+                    *s = sbox(*s);
+                }
+            }
+
+            let mut mixed = [Fr::zero(); WIDTH];
+            for (m, row) in mixed.iter_mut().zip(self.mds.iter()) {
+                *m = row
+                    .iter()
+                    .zip(state.iter())
+                    .fold(Fr::zero(), |acc, (a, s)| acc + a * s);
+            }
+            *state = mixed;
+        }
+    }
+}
+
+/// A transcript over a Poseidon sponge with rate 2 and capacity 1, the first
+/// element of the state.
+///
+/// Every value is absorbed as a sequence of elements, padded with zeros to a
+/// multiple of the rate, and each block of the sequence is added to the rate
+/// while the tag of the kind of the value is added to the capacity, before
+/// permuting. Scalars are the single element they are, and other encodings
+/// their length followed by chunks of 31 bytes read as little endian
+/// integers. A challenge adds its own tag to the capacity, permutes, and
+/// returns the second element of the state.
+#[derive(Clone, Debug)]
+pub struct PoseidonTranscript {
+    poseidon: &'static Poseidon,
+    state: [Fr; WIDTH],
+}
+
+impl PoseidonTranscript {
+    /// Creates a transcript for the protocol identified by `label`.
+    pub fn new(label: &[u8]) -> Self {
+        // The constants of the permutation are fixed
+        static POSEIDON: OnceLock<Poseidon> = OnceLock::new();
+        let mut transcript = PoseidonTranscript {
+            poseidon: POSEIDON.get_or_init(Poseidon::new),
+            state: [Fr::zero(); WIDTH],
+        };
+        transcript.absorb_bytes(label);
+        transcript
+    }
+
+    fn absorb_elements(&mut self, tag: u8, elements: &[Fr]) {
+        for block in elements.chunks(WIDTH - 1) {
+            self.state[0] += Fr::from(tag as u64);
+            for (s, element) in self.state[1..].iter_mut().zip(block.iter()) {
+                *s += element;
+            }
+            self.poseidon.permute(&mut self.state);
+        }
+    }
+}
+
+impl Transcript for PoseidonTranscript {
+    fn absorb_encoding(&mut self, kind: Kind, bytes: &[u8]) {
+        let mut elements = vec![Fr::from(bytes.len() as u64)];
+        elements.extend(bytes.chunks(31).map(|chunk| {
+            let mut wide = [0u8; 64];
+            wide[..chunk.len()].copy_from_slice(chunk);
+            Fr::from_bytes_wide(&wide)
+        }));
+        self.absorb_elements(kind as u8, &elements);
+    }
+
+    fn absorb_scalar(&mut self, scalar: &Fr) {
+        self.absorb_elements(Kind::Scalar as u8, &[*scalar]);
+    }
+
+    fn squeeze_challenge(&mut self) -> Fr {
+        self.state[0] += Fr::from(TAG_CHALLENGE as u64);
+        self.poseidon.permute(&mut self.state);
+        self.state[1]
+    }
+}
+
+#[cfg(test)]
+use crate::bn256::{Fq, Fq2, G1Affine, G2Affine};
+#[cfg(test)]
+use group::{prime::PrimeCurveAffine, Group};
+
+#[test]
+fn test_poseidon() {
+    let poseidon = Poseidon::new();
+    assert_eq!(poseidon.round_constants.len(), FULL_ROUNDS + PARTIAL_ROUNDS);
+    assert_eq!(
+        poseidon.round_constants[0][0],
+        Fr::from_raw([
+            0x8d21d47304cd8e6e,
+            0x14c4993c11bb2993,
+            0xd05986d656f40c21,
+            0x0ee9a592ba9a9518,
+        ])
+    );
+    assert_eq!(
+        poseidon.mds[0][0],
+        Fr::from_raw([
+            0xfedb68592ba8118b,
+            0x94be7c11ad24378b,
+            0xb2b70caf5c36a7b1,
+            0x109b7f411ba0e4c9,
+        ])
+    );
+
+    // poseidon([1, 2]) of circomlib
+    let mut state = [Fr::zero(), Fr::from(1), Fr::from(2)];
+    poseidon.permute(&mut state);
+    assert_eq!(
+        state[0],
+        Fr::from_raw([
+            0x9e19607a4417189a,
+            0x2a3617f274324551,
+            0x3df64c6b9662e9cf,
+            0x115cc0f5e7d69041,
+        ])
+    );
+}
+
+#[cfg(test)]
+fn test_transcript<T: Transcript + Clone>(new: impl Fn(&[u8]) -> T) {
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let gt = Gt::generator();
+
+    let mut transcript = new(&b"test"[..]);
+    transcript.absorb_scalar(&Fr::from(7));
+    transcript.absorb_field(&Fq::from(7));
+    transcript.absorb_field(&Fq2::one());
+    transcript.absorb_point(&g1);
+    transcript.absorb_point(&g2);
+    transcript.absorb_point(&G1Affine::identity());
+    transcript.absorb_gt(&gt);
+
+    // Challenges are deterministic and distinct
+    let challenge = transcript.clone().squeeze_challenge();
+    assert_eq!(challenge, transcript.squeeze_challenge());
+    assert!(challenge != transcript.squeeze_challenge());
+
+    let squeeze = |absorb: &dyn Fn(&mut T)| {
+        let mut transcript = new(&b"test"[..]);
+        absorb(&mut transcript);
+        transcript.squeeze_challenge()
+    };
+
+    // The label, the absorbed values and their order all matter
+    assert!(new(&b"other"[..]).squeeze_challenge() != squeeze(&|_| {}));
+    assert!(squeeze(&|t| t.absorb_scalar(&Fr::zero())) != squeeze(&|_| {}));
+    assert!(
+        squeeze(&|t| {
+            t.absorb_scalar(&Fr::one());
+            t.absorb_scalar(&Fr::zero());
+        }) != squeeze(&|t| {
+            t.absorb_scalar(&Fr::zero());
+            t.absorb_scalar(&Fr::one());
+        })
+    );
+    assert!(
+        squeeze(&|t| t.absorb_bytes(b"ab"))
+            != squeeze(&|t| {
+                t.absorb_bytes(b"a");
+                t.absorb_bytes(b"b");
+            })
+    );
+
+    // The identity, whose coordinates are zero in memory, is flagged
+    assert!(
+        squeeze(&|t| t.absorb_point(&G1Affine::identity())) != squeeze(&|t| t.absorb_point(&g1))
+    );
+    assert!(squeeze(&|t| t.absorb_gt(&gt)) != squeeze(&|t| t.absorb_gt(&(gt + gt))));
+
+    // Values of different kinds with the same encoding are distinct
+    let mut identity = vec![1u8];
+    identity.resize(65, 0);
+    assert!(
+        squeeze(&|t| t.absorb_point(&G1Affine::identity()))
+            != squeeze(&|t| t.absorb_bytes(&identity))
+    );
+    assert!(squeeze(&|t| t.absorb_gt(&gt)) != squeeze(&|t| t.absorb_fq12(&gt.0)));
+    assert!(
+        squeeze(&|t| t.absorb_field(&Fq::from(7))) != squeeze(&|t| t.absorb_scalar(&Fr::from(7)))
+    );
+    assert!(
+        squeeze(&|t| t.absorb_bytes(&[5]))
+            != squeeze(&|t| {
+                t.absorb_scalar(&Fr::one());
+                t.absorb_scalar(&Fr::from(5));
+            })
+    );
+
+    // Challenges are not absorbed values
+    assert!(
+        squeeze(&|t| {
+            t.absorb_scalar(&Fr::from(7));
+            t.squeeze_challenge();
+            t.absorb_scalar(&Fr::from(8));
+        }) != squeeze(&|t| {
+            t.absorb_scalar(&Fr::from(7));
+            t.absorb_scalar(&Fr::one());
+            t.absorb_scalar(&Fr::from(8));
+        })
+    );
+    assert!(
+        squeeze(&|t| {
+            t.squeeze_challenge();
+        }) != squeeze(&|t| t.absorb_scalar(&Fr::zero()))
+    );
+}
+
+#[test]
+fn test_transcripts() {
+    test_transcript(Blake2bTranscript::new);
+    test_transcript(KeccakTranscript::new);
+    test_transcript(PoseidonTranscript::new);
+}